@@ -0,0 +1,148 @@
+// boards as plain text, so that positions can be written by hand, shared and set up again exactly
+// the first line is the size as WIDTHxHEIGHT, followed by a line for each row with * for a mine and . for a safe tile:
+//
+//     5x3
+//     *....
+//     ...*.
+//     .....
+//
+// lines starting with # are comments, and are ignored along with blank lines
+
+use crate::files::{self, FileError};
+use crate::game::*;
+use std::{fs, path::Path};
+
+impl Minesweeper {
+    // the board as text, or None if the mines haven't been placed yet
+    pub fn board_text(&self) -> Option<String> {
+        if !self.mines_placed {
+            return None;
+        }
+        let mut text = format!("{}x{}\n", self.dimensions.x, self.dimensions.y);
+        for row in self.tiles.chunks(self.dimensions.x as usize) {
+            for tile in row {
+                text.push(match tile.mines {
+                    TileMines::Mine => '*',
+                    TileMines::NoMine(_) => '.',
+                });
+            }
+            text.push('\n');
+        }
+        Some(text)
+    }
+
+    pub fn export_board(&self, path: &Path) -> Result<(), FileError> {
+        let text = self.board_text().ok_or(FileError::InvalidBoard)?;
+        files::write_text(path, &text)
+    }
+
+    pub fn import_board(path: &Path) -> Result<Self, FileError> {
+        Self::from_board_text(&fs::read_to_string(path)?)
+    }
+
+    // a new game with the mines where the text says, which is practice as the board wasn't generated
+    pub fn from_board_text(text: &str) -> Result<Self, FileError> {
+        let parse_error = |line: usize, message: String| FileError::Parse { line, message };
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (header_line, header) = lines
+            .next()
+            .ok_or_else(|| parse_error(1, "the file is empty".to_string()))?;
+        let (width, height) = header
+            .split_once(['x', 'X'])
+            .and_then(|(width, height)| {
+                Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+            })
+            .ok_or_else(|| {
+                parse_error(
+                    header_line,
+                    format!(
+                        "expected the size of the board as WIDTHxHEIGHT, but found \"{header}\""
+                    ),
+                )
+            })?;
+
+        let mut mines = Vec::new();
+        let mut rows: u16 = 0;
+        let mut last_line = header_line;
+        for (line_number, line) in lines {
+            last_line = line_number;
+            if rows == height {
+                return Err(parse_error(
+                    line_number,
+                    format!("there are more rows than the {height} given in the size"),
+                ));
+            }
+            if line.chars().count() != width as usize {
+                return Err(parse_error(
+                    line_number,
+                    format!(
+                        "the row has {} tiles, but the board is {width} wide",
+                        line.chars().count()
+                    ),
+                ));
+            }
+            let row_mines = files::board_row_mines(line, rows, &['.']).map_err(|c| {
+                parse_error(
+                    line_number,
+                    format!("'{c}' isn't a tile, use * for a mine and . for a safe tile"),
+                )
+            })?;
+            mines.extend(row_mines);
+            rows += 1;
+        }
+        if rows != height {
+            return Err(parse_error(
+                last_line,
+                format!("expected {height} rows, but found {rows}"),
+            ));
+        }
+
+        let difficulty = Difficulty::custom_layout(width, height, mines.len() as u32)
+            .map_err(|e| parse_error(header_line, e.to_string()))?;
+        let difficulty = files::standard_difficulty(difficulty);
+
+        let mut game = Minesweeper::with_layout(difficulty, &mines);
+        game.practice = true;
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the line a mistake in the text is reported on
+    fn error_line(text: &str) -> usize {
+        match Minesweeper::from_board_text(text) {
+            Err(FileError::Parse { line, .. }) => line,
+            Err(e) => panic!("expected a mistake on a line, but got: {e}"),
+            Ok(_) => panic!("expected a mistake on a line, but the board was read"),
+        }
+    }
+
+    #[test]
+    fn reads_a_board_with_comments() {
+        let game =
+            Minesweeper::from_board_text("# a board\n3x2\n\n*..\n# the last row\n..*\n").unwrap();
+        assert_eq!(game.board_text().unwrap(), "3x2\n*..\n..*\n");
+        assert!(game.practice);
+    }
+
+    #[test]
+    fn mistakes_are_reported_on_their_line() {
+        assert_eq!(error_line(""), 1);
+        assert_eq!(error_line("# only a comment\n"), 1);
+        assert_eq!(error_line("# a board\n3 by 2\n*..\n..*"), 2);
+        // blank lines and comments still count towards the line numbers
+        assert_eq!(error_line("3x2\n\n*..\n# a comment\n..*."), 5);
+        assert_eq!(error_line("3x2\n*..\n.?*"), 3);
+        assert_eq!(error_line("3x3\n*..\n..*\n"), 3);
+        assert_eq!(error_line("3x2\n*..\n..*\n...\n"), 4);
+        assert_eq!(error_line("0x2\n"), 1);
+    }
+}
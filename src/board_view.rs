@@ -0,0 +1,125 @@
+// the part of the board which is visible in the window, which can be scrolled and zoomed
+// so that boards which are too big to fit on the screen can still be played
+
+use minesweeper::game::*;
+
+use crate::consts::*;
+
+pub struct BoardView {
+    // the size of each tile on the screen
+    pub tile_size: f64,
+    // how far the visible area is scrolled from the top left of the board, in pixels
+    pub offset: [f64; 2],
+    // the position and size of the visible area in the window
+    pub rect: [f64; 4],
+    // the size of the board in tiles
+    dimensions: [f64; 2],
+}
+
+impl BoardView {
+    pub fn new(game: &Minesweeper) -> Self {
+        let dimensions = [game.dimensions.x as f64, game.dimensions.y as f64];
+        BoardView {
+            tile_size: TILE_SIZE,
+            offset: [0., 0.],
+            rect: [
+                BORDER_SIZE,
+                BORDER_SIZE + BORDER_MIDDLE_POS,
+                f64::min(dimensions[0], MAX_VISIBLE_TILES[0]) * TILE_SIZE,
+                f64::min(dimensions[1], MAX_VISIBLE_TILES[1]) * TILE_SIZE,
+            ],
+            dimensions,
+        }
+    }
+
+    pub fn scroll(&mut self, dx: f64, dy: f64) {
+        self.offset[0] += dx;
+        self.offset[1] += dy;
+        self.clamp();
+    }
+
+    // zoom in (factor > 1) or out (factor < 1), keeping the point under the mouse in the same place
+    pub fn zoom(&mut self, factor: f64, mouse_pos: [f64; 2]) {
+        let new_tile_size = (self.tile_size * factor).clamp(MIN_TILE_SIZE, MAX_TILE_SIZE);
+        for (i, mouse_pos) in mouse_pos.into_iter().enumerate() {
+            let mouse_offset = mouse_pos - self.rect[i];
+            let board_pos = (self.offset[i] + mouse_offset) / self.tile_size;
+            self.offset[i] = board_pos * new_tile_size - mouse_offset;
+        }
+        self.tile_size = new_tile_size;
+        self.clamp();
+    }
+
+    // scroll just enough for a tile to be fully visible, so the keyboard cursor can't move out of view
+    pub fn scroll_to(&mut self, coord: Coord) {
+        for (i, pos) in [coord.x, coord.y].into_iter().enumerate() {
+            let start = pos as f64 * self.tile_size;
+            self.offset[i] = self.offset[i]
+                .min(start)
+                .max(start + self.tile_size - self.rect[i + 2]);
+        }
+        self.clamp();
+    }
+
+    // stop the board from being scrolled out of view, and centre it if it is smaller than the visible area
+    fn clamp(&mut self) {
+        for i in 0..2 {
+            let board_size = self.dimensions[i] * self.tile_size;
+            let visible_size = self.rect[i + 2];
+            self.offset[i] = if board_size < visible_size {
+                (board_size - visible_size) / 2.
+            } else {
+                self.offset[i].clamp(0., board_size - visible_size)
+            };
+        }
+    }
+
+    // the tile that is at a position in the window, if any
+    pub fn coord_at(&self, pos: [f64; 2]) -> Option<Coord> {
+        let x = (pos[0] - self.rect[0] + self.offset[0]) / self.tile_size;
+        let y = (pos[1] - self.rect[1] + self.offset[1]) / self.tile_size;
+        let in_view = pos[0] >= self.rect[0]
+            && pos[0] < self.rect[0] + self.rect[2]
+            && pos[1] >= self.rect[1]
+            && pos[1] < self.rect[1] + self.rect[3];
+        if in_view && x >= 0. && y >= 0. && x < self.dimensions[0] && y < self.dimensions[1] {
+            Some(Coord {
+                x: x as u16,
+                y: y as u16,
+            })
+        } else {
+            None
+        }
+    }
+
+    // the range of tiles (inclusive) which are at least partly visible
+    pub fn visible_coords(&self) -> (Coord, Coord) {
+        let min = |i: usize| (self.offset[i] / self.tile_size).max(0.);
+        let max = |i: usize| {
+            ((self.offset[i] + self.rect[i + 2]) / self.tile_size)
+                .ceil()
+                .min(self.dimensions[i])
+                - 1.
+        };
+        (
+            Coord {
+                x: min(0) as u16,
+                y: min(1) as u16,
+            },
+            Coord {
+                x: max(0) as u16,
+                y: max(1) as u16,
+            },
+        )
+    }
+
+    // where a tile is drawn in the window
+    pub fn tile_rect(&self, coord: Coord) -> [f64; 4] {
+        [
+            self.rect[0] + coord.x as f64 * self.tile_size - self.offset[0],
+            self.rect[1] + coord.y as f64 * self.tile_size - self.offset[1],
+            self.tile_size,
+            self.tile_size,
+        ]
+    }
+}
@@ -7,9 +7,40 @@ pub const BORDER_MIDDLE_POS: f64 = TILE_SIZE * 2.5;
 pub const BORDER_WIDE_SCALE_FACTOR: f64 = 2.5;
 
 pub const FACE_BUTTON_SIZE: f64 = (BORDER_MIDDLE_POS - BORDER_SIZE) * 0.9;
+// the hint button sits in the gap between the face button and the stopwatch, which is small on narrow boards
+pub const HINT_BUTTON_SIZE: f64 = FACE_BUTTON_SIZE * 0.45;
 
 pub const DIFFICULTY_BUTTON_HEIGHT: f64 = TILE_SIZE;
 pub const DIFFICULTY_BUTTON_SPACING: f64 = TILE_SIZE * 0.5;
 
+// the width of a number display with three digits
 pub const NUM_DISPLAY_WIDTH: f64 = (274. / 170.) * FACE_BUTTON_SIZE;
 pub const NUM_DISPLAY_HEIGHT: f64 = FACE_BUTTON_SIZE;
+// number displays are put together from parts of the background texture, so they can have any number of digits
+// these are the sizes of the parts in the texture's pixels: the edges on each side, the room for a digit or a
+// decimal point, and the gap between them
+pub const NUM_DISPLAY_TEXTURE_HEIGHT: f64 = 170.;
+pub const NUM_DISPLAY_TEXTURE_EDGE: f64 = 14.;
+pub const NUM_DISPLAY_TEXTURE_DIGIT: f64 = 74.;
+pub const NUM_DISPLAY_TEXTURE_POINT: f64 = 18.;
+pub const NUM_DISPLAY_TEXTURE_GAP: f64 = 12.;
+
+// the limits for custom boards entered in the window
+pub const CUSTOM_MIN_SIZE: u16 = 9;
+pub const CUSTOM_MAX_SIZE: u16 = 1000;
+
+// boards with more tiles than this are shown in a board_view which can be scrolled and zoomed
+pub const MAX_VISIBLE_TILES: [f64; 2] = [40., 24.];
+pub const MIN_TILE_SIZE: f64 = 8.;
+pub const MAX_TILE_SIZE: f64 = TILE_SIZE * 4.;
+// how far one notch of the mouse wheel scrolls or zooms the board
+pub const SCROLL_SPEED: f64 = TILE_SIZE;
+pub const ZOOM_SPEED: f64 = 1.25;
+// the limits for scaling the whole window with ctrl+= and ctrl+-
+pub const MIN_WINDOW_SCALE: f64 = 0.5;
+pub const MAX_WINDOW_SCALE: f64 = 4.;
+
+pub const TEXT_SIZE: u32 = 16;
+// dialogs are drawn over the board, so must fit inside the smallest board
+pub const DIALOG_WIDTH: f64 = TILE_SIZE * CUSTOM_MIN_SIZE as f64 - 16.;
+pub const DIALOG_HEIGHT: f64 = 200.;
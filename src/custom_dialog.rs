@@ -0,0 +1,143 @@
+// the dialog which is shown over the board to enter the size and number of mines of a custom board
+
+use minesweeper::game::*;
+use piston_window::*;
+
+use crate::consts::*;
+use crate::dialog::*;
+use crate::rects::*;
+
+const FIELD_NAMES: [&str; 3] = ["Width", "Height", "Mines"];
+
+pub struct CustomDialog {
+    // the text typed into the width, height and mines fields
+    fields: [String; 3],
+    selected: usize,
+    error: Option<String>,
+}
+
+impl CustomDialog {
+    pub fn new(difficulty: Difficulty) -> Self {
+        // start with the values of the current board so they only need tweaking
+        let (dimensions, mines) = difficulty.board_info();
+        CustomDialog {
+            fields: [
+                dimensions.x.to_string(),
+                dimensions.y.to_string(),
+                mines.to_string(),
+            ],
+            selected: 0,
+            error: None,
+        }
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars().filter(|c| c.is_ascii_digit()) {
+            if self.fields[self.selected].len() < 7 {
+                self.fields[self.selected].push(c);
+            }
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.fields[self.selected].pop();
+    }
+
+    pub fn next_field(&mut self) {
+        self.selected = (self.selected + 1) % self.fields.len();
+    }
+
+    // select the field that was clicked on, if any
+    pub fn click(&mut self, mouse_pos: [f64; 2], dialog_rect: [f64; 4]) {
+        for i in 0..self.fields.len() {
+            if mouse_intersects_rect(mouse_pos, field_rect(dialog_rect, i)) {
+                self.selected = i;
+            }
+        }
+    }
+
+    // returns the entered difficulty, or shows an error in the dialog if the values aren't valid
+    pub fn submit(&mut self) -> Option<Difficulty> {
+        match self.parse() {
+            Ok(difficulty) => Some(difficulty),
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+
+    fn parse(&self) -> Result<Difficulty, String> {
+        let mut values = [0; 3];
+        for (i, field) in self.fields.iter().enumerate() {
+            values[i] = field
+                .parse::<u32>()
+                .map_err(|_| format!("{} must be a number", FIELD_NAMES[i].to_lowercase()))?;
+        }
+        let [width, height, mines] = values;
+        let size_range = CUSTOM_MIN_SIZE as u32..=CUSTOM_MAX_SIZE as u32;
+        if !size_range.contains(&width) || !size_range.contains(&height) {
+            return Err(format!("size must be {CUSTOM_MIN_SIZE}-{CUSTOM_MAX_SIZE}"));
+        }
+        Difficulty::custom(width as u16, height as u16, mines).map_err(|e| e.to_string())
+    }
+
+    pub fn draw(&self, dialog_rect: [f64; 4], c: Context, g: &mut G2d, glyphs: &mut Glyphs) {
+        let transform = draw_dialog_frame(dialog_rect, c, g);
+        draw_lines([("Custom board", TEXT_SIZE, 28.)], transform, c, g, glyphs);
+
+        for (i, name) in FIELD_NAMES.iter().enumerate() {
+            let rect = field_rect(dialog_rect, i);
+            text::Text::new_color(DIALOG_TEXT_COLOR, TEXT_SIZE)
+                .draw(
+                    name,
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(dialog_rect[0] + 16., rect[1] + 18.),
+                    g,
+                )
+                .unwrap();
+            rectangle([1.; 4], rect, c.transform, g);
+            if i == self.selected {
+                Rectangle::new_border(DIALOG_TEXT_COLOR, 1.).draw(
+                    rect,
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+            text::Text::new_color(DIALOG_TEXT_COLOR, TEXT_SIZE)
+                .draw(
+                    &self.fields[i],
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(rect[0] + 6., rect[1] + 18.),
+                    g,
+                )
+                .unwrap();
+        }
+
+        if let Some(error) = &self.error {
+            text::Text::new_color([0.8, 0., 0., 1.], TEXT_SIZE - 4)
+                .draw(error, glyphs, &c.draw_state, transform.trans(16., 164.), g)
+                .unwrap();
+        }
+        draw_lines(
+            [("Tab: next field, Enter: start", TEXT_SIZE - 4, 188.)],
+            transform,
+            c,
+            g,
+            glyphs,
+        );
+    }
+}
+
+// the text box for each field, relative to the dialog
+fn field_rect(dialog_rect: [f64; 4], i: usize) -> [f64; 4] {
+    [
+        dialog_rect[0] + 96.,
+        dialog_rect[1] + 44. + i as f64 * 34.,
+        80.,
+        26.,
+    ]
+}
@@ -0,0 +1,34 @@
+// what the dialogs shown over the board have in common: a grey box with a border, and lines of dark text in it
+
+use piston_window::*;
+
+pub const DIALOG_TEXT_COLOR: [f32; 4] = [0.2, 0.2, 0.2, 1.];
+
+// draws the background and border of a dialog, returning the transform for drawing in it
+// (so positions in the dialog are from its top left corner)
+pub fn draw_dialog_frame(dialog_rect: [f64; 4], c: Context, g: &mut G2d) -> math::Matrix2d {
+    rectangle([0.75, 0.75, 0.75, 1.], dialog_rect, c.transform, g);
+    Rectangle::new_border([0.5, 0.5, 0.5, 1.], 2.).draw(dialog_rect, &c.draw_state, c.transform, g);
+    c.transform.trans(dialog_rect[0], dialog_rect[1])
+}
+
+// draws lines of text down the left of a dialog, each as the text, its size and how far down the dialog it is
+pub fn draw_lines<S: AsRef<str>>(
+    lines: impl IntoIterator<Item = (S, u32, f64)>,
+    transform: math::Matrix2d,
+    c: Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+) {
+    for (line, size, y) in lines {
+        text::Text::new_color(DIALOG_TEXT_COLOR, size)
+            .draw(
+                line.as_ref(),
+                glyphs,
+                &c.draw_state,
+                transform.trans(16., y),
+                g,
+            )
+            .unwrap();
+    }
+}
@@ -0,0 +1,54 @@
+// the dialog shown over the board when something went wrong, like a file or the clipboard that couldn't be opened,
+// or a file the game keeps that couldn't be saved, saying what was wrong
+// (the game may be run without a console, so errors printed there wouldn't be seen)
+
+use piston_window::*;
+
+use crate::consts::*;
+use crate::dialog::*;
+
+pub fn draw_error_dialog(
+    message: &str,
+    dialog_rect: [f64; 4],
+    c: Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+) {
+    let transform = draw_dialog_frame(dialog_rect, c, g);
+
+    let mut lines = vec![("Something went wrong".to_string(), TEXT_SIZE, 28.)];
+    // as many lines of the message as fit above the last line
+    let wrapped = wrap_text(message, TEXT_SIZE - 4, dialog_rect[2] - 32., glyphs);
+    for (i, line) in wrapped.into_iter().take(7).enumerate() {
+        lines.push((line, TEXT_SIZE - 4, 56. + i as f64 * 16.));
+    }
+    lines.push((
+        "Press any key or click to close".to_string(),
+        TEXT_SIZE - 4,
+        188.,
+    ));
+    draw_lines(lines, transform, c, g, glyphs);
+}
+
+// splits text into lines no wider than the width, breaking between words
+// (a word too long for a line of its own is left to run over the edge)
+fn wrap_text(text: &str, size: u32, width: f64, glyphs: &mut Glyphs) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let longer = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{line} {word}")
+        };
+        if !line.is_empty() && glyphs.width(size, &longer).unwrap_or(0.) > width {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = longer;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
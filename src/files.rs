@@ -0,0 +1,115 @@
+// reading and writing the files used for replays, saved games and boards, and the errors they can cause
+
+use crate::game::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::{error, fmt, fs, io, path::Path};
+
+#[derive(Debug)]
+pub enum FileError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    // the file was saved by a different version of the game
+    Version { found: u32, supported: u32 },
+    // the board size isn't valid, or the mines or moves are outside it
+    InvalidBoard,
+    // a text file has a mistake on a line (counting from 1)
+    Parse { line: usize, message: String },
+    // the file uses a feature of its format which the game doesn't have
+    Unsupported(String),
+}
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileError::Io(e) => write!(f, "couldn't read or write the file: {e}"),
+            FileError::Format(e) => write!(f, "the file isn't in the right format: {e}"),
+            FileError::Version { found, supported } => write!(
+                f,
+                "the file is version {found}, but only version {supported} is supported"
+            ),
+            FileError::InvalidBoard => {
+                write!(f, "the board is invalid, or has mines or moves outside it")
+            }
+            FileError::Parse { line, message } => write!(f, "line {line}: {message}"),
+            FileError::Unsupported(feature) => write!(f, "not supported: {feature}"),
+        }
+    }
+}
+impl error::Error for FileError {}
+impl From<io::Error> for FileError {
+    fn from(e: io::Error) -> Self {
+        FileError::Io(e)
+    }
+}
+impl From<serde_json::Error> for FileError {
+    fn from(e: serde_json::Error) -> Self {
+        FileError::Format(e)
+    }
+}
+
+// writes a file, making the folder it goes in if it doesn't exist yet
+pub(crate) fn write_text(path: &Path, text: &str) -> Result<(), FileError> {
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder)?;
+    }
+    fs::write(path, text)?;
+    Ok(())
+}
+
+pub(crate) fn write_json(path: &Path, value: &impl Serialize) -> Result<(), FileError> {
+    write_text(path, &serde_json::to_string(value)?)
+}
+
+// the file must have a "version" field, which is checked first as the rest of the file
+// may be completely different in other versions
+pub(crate) fn read_json<T: DeserializeOwned>(path: &Path, version: u32) -> Result<T, FileError> {
+    let text = fs::read_to_string(path)?;
+    #[derive(Deserialize)]
+    struct Version {
+        version: u32,
+    }
+    let found = serde_json::from_str::<Version>(&text)?.version;
+    if found != version {
+        return Err(FileError::Version {
+            found,
+            supported: version,
+        });
+    }
+    Ok(serde_json::from_str(&text)?)
+}
+
+// the mines in a row of a board written as text, with * for a mine and any of the safe characters for a safe tile,
+// or the first character which isn't a tile
+pub(crate) fn board_row_mines(line: &str, y: u16, safe: &[char]) -> Result<Vec<Coord>, char> {
+    let mut mines = Vec::new();
+    for (x, c) in line.chars().enumerate() {
+        match c {
+            '*' => mines.push(Coord { x: x as u16, y }),
+            _ if safe.contains(&c) => {}
+            _ => return Err(c),
+        }
+    }
+    Ok(mines)
+}
+
+// the normal difficulty with the same board as a custom one if there is one, so a board from a file is shown as that
+// difficulty
+pub(crate) fn standard_difficulty(difficulty: Difficulty) -> Difficulty {
+    [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard]
+        .into_iter()
+        .find(|d| d.board_info() == difficulty.board_info())
+        .unwrap_or(difficulty)
+}
+
+// the dimensions of the board for a difficulty loaded from a file, which may not have been validated
+pub(crate) fn checked_dimensions(difficulty: Difficulty) -> Result<Coord, FileError> {
+    if let Difficulty::Custom {
+        width,
+        height,
+        mines,
+    } = difficulty
+    {
+        Difficulty::custom_layout(width, height, mines).map_err(|_| FileError::InvalidBoard)?;
+    }
+    Ok(difficulty.board_info().0)
+}
@@ -1,8 +1,17 @@
 // all the game logic for minesweeper is in this file (completely independent of the graphics)
 
-use rand::Rng;
-use std::cmp;
-use stopwatch::Stopwatch;
+use crate::replay::{Action, Replay, ReplayEvent};
+use crate::stopwatch::Stopwatch;
+use crate::{metrics, probability, replay, solver};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::{cmp, error, fmt};
+
+// how many tiles worth of boards to try generating in no guess mode before giving up
+// (bigger boards get fewer attempts, and this is a fixed number rather than a time limit so seeds stay reproducible)
+const NO_GUESS_TILE_BUDGET: usize = 2_000_000;
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum GameState {
@@ -11,12 +20,106 @@ pub enum GameState {
     Lost,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Difficulty {
     Easy,
     Medium,
     Hard,
+    // custom difficulties should be created with Difficulty::custom so that the mine count is validated
+    Custom { width: u16, height: u16, mines: u32 },
 }
+impl Difficulty {
+    // creates a custom difficulty, checking that the mines will fit on the board
+    pub fn custom(width: u16, height: u16, mines: u32) -> Result<Self, CustomBoardError> {
+        if width == 0 || height == 0 {
+            return Err(CustomBoardError::EmptyBoard);
+        }
+        let max = max_mines(width, height);
+        if mines > max {
+            return Err(CustomBoardError::TooManyMines { max });
+        }
+        Ok(Difficulty::Custom {
+            width,
+            height,
+            mines,
+        })
+    }
+
+    // creates a custom difficulty for a board whose mines are already placed (like an imported board), which doesn't
+    // need the space gen_map keeps free around the first click, so it can be full of mines
+    pub fn custom_layout(width: u16, height: u16, mines: u32) -> Result<Self, CustomBoardError> {
+        if width == 0 || height == 0 {
+            return Err(CustomBoardError::EmptyBoard);
+        }
+        let max = width as u32 * height as u32;
+        if mines > max {
+            return Err(CustomBoardError::TooManyMines { max });
+        }
+        Ok(Difficulty::Custom {
+            width,
+            height,
+            mines,
+        })
+    }
+
+    // this is where the dimensions and no of mines for each difficulty are defined
+    pub fn board_info(&self) -> (Coord, u32) {
+        match *self {
+            Difficulty::Easy => (Coord { x: 9, y: 9 }, 10),
+            Difficulty::Medium => (Coord { x: 16, y: 16 }, 40),
+            Difficulty::Hard => (Coord { x: 30, y: 16 }, 100),
+            Difficulty::Custom {
+                width,
+                height,
+                mines,
+            } => (
+                Coord {
+                    x: width,
+                    y: height,
+                },
+                mines,
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Medium => write!(f, "Medium"),
+            Difficulty::Hard => write!(f, "Hard"),
+            Difficulty::Custom {
+                width,
+                height,
+                mines,
+            } => write!(f, "Custom {width}x{height}, {mines} mines"),
+        }
+    }
+}
+
+// the maximum number of mines that can be placed on a board, as gen_map keeps the 3x3 area around the first click free
+pub fn max_mines(width: u16, height: u16) -> u32 {
+    let safe_area = cmp::min(width as u32, 3) * cmp::min(height as u32, 3);
+    width as u32 * height as u32 - safe_area
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CustomBoardError {
+    EmptyBoard,
+    TooManyMines { max: u32 },
+}
+impl fmt::Display for CustomBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CustomBoardError::EmptyBoard => write!(f, "the board must be at least 1x1"),
+            CustomBoardError::TooManyMines { max } => {
+                write!(f, "too many mines, the maximum for this board is {max}")
+            }
+        }
+    }
+}
+impl error::Error for CustomBoardError {}
 
 #[derive(PartialEq)]
 pub enum TileMines {
@@ -25,18 +128,35 @@ pub enum TileMines {
     Mine,
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum TileState {
     Closed,
     Flagged,
+    // a closed tile marked as one the player isn't sure about, which is treated like any other closed tile
+    // (only used if question marks are turned on)
+    Questioned,
     Open,
 }
 
 // struct used throughout the program to store coordinates
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Coord {
-    pub x: u8,
-    pub y: u8,
+    pub x: u16,
+    pub y: u16,
+}
+
+// returns an iterator over each tile within a distance of a coord on a board with the given dimensions
+// (not including the coord itself)
+pub fn tiles_around(dimensions: Coord, coord: Coord, distance: u16) -> impl Iterator<Item = Coord> {
+    // the min and max values for the x and y coords which can be iterated over,
+    // prevented from being out of bounds by saturating at 0 and capping at the edge of the board
+    let xs = coord.x.saturating_sub(distance)
+        ..=cmp::min(coord.x.saturating_add(distance), dimensions.x - 1);
+    let ys = coord.y.saturating_sub(distance)
+        ..=cmp::min(coord.y.saturating_add(distance), dimensions.y - 1);
+
+    ys.flat_map(move |y| xs.clone().map(move |x| Coord { x, y }))
+        .filter(move |&tile| tile != coord)
 }
 
 // struct for storing each tile
@@ -56,30 +176,71 @@ impl Tile {
     }
 }
 
+// a tile suggested to the player to open next
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hint {
+    pub coord: Coord,
+    // the chance of the tile being a mine, which is 0 if it is definitely safe
+    pub mine_chance: f64,
+}
+
+// the tiles changed by one open or flag, so that it can be undone and redone
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Move {
+    // the index of each tile which changed, with its state before and after the move
+    pub(crate) changes: Vec<(usize, TileState, TileState)>,
+    pub(crate) mines_left_before: i64,
+    pub(crate) mines_left_after: i64,
+}
+
 pub struct Minesweeper {
     // stores the whole game state
     pub dimensions: Coord,
-    mines: u8,
+    mines: u32,
     pub state: GameState,
     pub difficulty: Difficulty,
     pub tiles: Vec<Tile>,
-    first_go: bool,
+    pub(crate) first_go: bool,
+    // whether the mines have been placed, which is normally done when the first tile is opened
+    pub(crate) mines_placed: bool,
+    // worked out once the mines are placed, as it doesn't change during the game
+    bbbv: u32,
     pub stopwatch: Stopwatch,
-    pub mines_left: i32,
+    pub mines_left: i64,
+    // the board is generated from this seed and the first tile opened, so games can be reproduced
+    pub seed: u64,
+    // whether the board is generated so that it can be solved without guessing
+    // (set back to false when the mines are placed if no board like that could be found)
+    pub no_guess: bool,
+    // whether flagging a flagged tile marks it with a question mark rather than closing it again
+    pub question_marks: bool,
+    // how many hints have been given, kept with the result so hinted games can be left out of records
+    pub hints_used: u32,
+    // set when a move is undone or the board was imported, so the game doesn't count towards statistics
+    pub practice: bool,
+    // the moves which can be undone and redone, and the changes made so far by the move in progress
+    pub(crate) moves: Vec<Move>,
+    pub(crate) undone: Vec<Move>,
+    changes: Vec<(usize, TileState, TileState)>,
+    // every move made, with when it was made, so the game can be replayed
+    pub(crate) events: Vec<ReplayEvent>,
 }
 impl Minesweeper {
-    pub fn new(difficulty: Difficulty) -> Self {
-        // this is where the dimensions and no of mines for each difficulty are defined
-        let game_info: (Coord, u8) = match difficulty {
-            Difficulty::Easy => (Coord { x: 9, y: 9 }, 10),
-            Difficulty::Medium => (Coord { x: 16, y: 16 }, 40),
-            Difficulty::Hard => (Coord { x: 30, y: 16 }, 100),
-        };
+    pub fn new(difficulty: Difficulty, no_guess: bool) -> Self {
+        Self::with_seed(difficulty, no_guess, rand::random())
+    }
+
+    pub fn with_seed(difficulty: Difficulty, no_guess: bool, seed: u64) -> Self {
+        let mut game_info = difficulty.board_info();
+        // a custom difficulty may have been constructed without Difficulty::custom,
+        // so clamp the mines to make sure gen_map can always find space for them
+        game_info.1 = cmp::min(game_info.1, max_mines(game_info.0.x, game_info.0.y));
 
         // construct the tiles vector, pushing in the correct number of tiles to fill the board
-        let mut tiles = Vec::new();
-        for x in 0..game_info.0.x {
-            for y in 0..game_info.0.y {
+        // (in the same row by row order that get_tile indexes them)
+        let mut tiles = Vec::with_capacity(game_info.0.x as usize * game_info.0.y as usize);
+        for y in 0..game_info.0.y {
+            for x in 0..game_info.0.x {
                 tiles.push(Tile::new(Coord { x, y }));
             }
         }
@@ -91,9 +252,72 @@ impl Minesweeper {
             difficulty,
             tiles,
             first_go: true,
+            mines_placed: false,
+            bbbv: 0,
             stopwatch: Stopwatch::new(),
-            mines_left: game_info.1 as i32,
+            mines_left: game_info.1 as i64,
+            seed,
+            no_guess,
+            question_marks: false,
+            hints_used: 0,
+            practice: false,
+            moves: Vec::new(),
+            undone: Vec::new(),
+            changes: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    // creates a game with the mines in the given places rather than generating them when the first tile is opened
+    // (duplicate coords are only counted once, and coords outside the board cause a panic)
+    pub fn with_layout(difficulty: Difficulty, mines: &[Coord]) -> Self {
+        let mut game = Self::with_seed(difficulty, false, 0);
+        game.mines = 0;
+        for &coord in mines {
+            if game.get_tile(coord).mines != TileMines::Mine {
+                game.place_mine(coord);
+                game.mines += 1;
+            }
         }
+        game.mines_left = game.mines as i64;
+        game.mines_placed = true;
+        game.bbbv = metrics::count_bbbv(&game).0;
+        game
+    }
+
+    // whether the game can go in the statistics and high scores, which it can't if hints were used or moves undone
+    pub fn counts_towards_records(&self) -> bool {
+        !self.practice && self.hints_used == 0
+    }
+
+    // whether the first tile has been opened, which is when the stopwatch starts
+    pub fn started(&self) -> bool {
+        !self.first_go
+    }
+
+    // a recording of the game so far, which can be watched back or saved
+    pub fn replay(&self) -> Replay {
+        Replay {
+            version: replay::VERSION,
+            difficulty: self.difficulty,
+            seed: self.seed,
+            no_guess: self.no_guess,
+            question_marks: self.question_marks,
+            mines: self
+                .tiles
+                .iter()
+                .filter(|tile| tile.mines == TileMines::Mine)
+                .map(|tile| tile.coord)
+                .collect(),
+            events: self.events.clone(),
+            hints_used: self.hints_used,
+            metrics: self.metrics(),
+        }
+    }
+
+    // the total number of mines on the board
+    pub fn mines(&self) -> u32 {
+        self.mines
     }
 
     // helper functions to return a (mutable) reference to the tile at a given coord
@@ -106,55 +330,58 @@ impl Minesweeper {
 
     // function which returns an iterator over each neighbour of a given coord
     pub fn iter_neighbours(&self, coord: Coord) -> impl Iterator<Item = Coord> {
-        // variables to store the min and max values for the x and y coords which can be iterated over
-        // coords are prevented from being out of bounds using the cmp::max and cmp::min functions
-        let x = cmp::max(coord.x as i32 - 1, 0);
-        let x2 = cmp::min(coord.x as i32 + 1, self.dimensions.x as i32 - 1);
-
-        let y = cmp::max(coord.y as i32 - 1, 0);
-        let y2 = cmp::min(coord.y as i32 + 1, self.dimensions.y as i32 - 1);
-
-        let mut coords = Vec::new();
-        for y in y as u8..=y2 as u8 {
-            for x in x as u8..=x2 as u8 {
-                if x == coord.x && y == coord.y {
-                    continue;
-                }
-                // add each coord surrounding the inputted coord to the 'coords' vector
-                coords.push(Coord { x, y });
-            }
-        }
-        // return the coords vector as an iterator
-        coords.into_iter()
+        tiles_around(self.dimensions, coord, 1)
     }
 
     fn gen_map(&mut self, start_coord: Coord) {
-        for _ in 0..self.mines {
-            let mut tile_coord: Coord;
-            loop {
-                // keep trying until we find a tile with no mine and not a neighbour of the start tile
-                tile_coord = Coord {
-                    x: rand::thread_rng().gen_range(0..=(self.dimensions.x - 1)),
-                    y: rand::thread_rng().gen_range(0..=(self.dimensions.y - 1)),
-                };
-                if self.get_tile(tile_coord).mines == TileMines::Mine
-                    || (tile_coord.x as i32 - start_coord.x as i32).abs() <= 1
-                        && (tile_coord.y as i32 - start_coord.y as i32).abs() <= 1
-                {
-                    // if the tile is the start tile, then continue (this is needed bc iter_neighbours doesn't iter over the tile itself)
-                    continue;
-                }
-                break;
+        // ChaCha is used as its output is the same on every platform, so a seed always gives the same board
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let attempts = cmp::max(NO_GUESS_TILE_BUDGET / self.tiles.len(), 1);
+        for _ in 0..attempts {
+            self.place_mines(start_coord, &mut rng);
+            // in no guess mode, keep generating boards until one can be solved from the start tile without guessing
+            if !self.no_guess || solver::is_solvable(self, start_coord) {
+                self.bbbv = metrics::count_bbbv(self).0;
+                return;
             }
-            // assign the mine
-            self.get_tile_mut(tile_coord).mines = TileMines::Mine;
-
-            for neighbour in self.iter_neighbours(tile_coord) {
-                if let TileMines::NoMine(n) = self.get_tile(neighbour).mines {
-                    if n < 8 {
-                        // increment mines variables for tiles around each mine
-                        self.get_tile_mut(neighbour).mines = TileMines::NoMine(n + 1);
-                    }
+            for tile in self.tiles.iter_mut() {
+                tile.mines = TileMines::NoMine(0);
+            }
+        }
+        // if no solvable board was found (e.g. the board is very big or has a lot of mines), just use a random one,
+        // and stop claiming it can be solved without guessing
+        self.no_guess = false;
+        self.place_mines(start_coord, &mut rng);
+        self.bbbv = metrics::count_bbbv(self).0;
+    }
+
+    fn place_mines(&mut self, start_coord: Coord, rng: &mut ChaCha8Rng) {
+        // every tile which can have a mine, i.e. all the tiles except the start tile and its neighbours
+        let mut candidates: Vec<Coord> = self
+            .tiles
+            .iter()
+            .map(|tile| tile.coord)
+            .filter(|coord| {
+                coord.x.abs_diff(start_coord.x) > 1 || coord.y.abs_diff(start_coord.y) > 1
+            })
+            .collect();
+        // pick the mines by shuffling just enough of the candidates, rather than retrying random tiles
+        // until we find one without a mine (which gets very slow on big boards with lots of mines)
+        let (mine_coords, _) = candidates.partial_shuffle(rng, self.mines as usize);
+        for &tile_coord in mine_coords.iter() {
+            self.place_mine(tile_coord);
+        }
+    }
+
+    fn place_mine(&mut self, coord: Coord) {
+        // assign the mine
+        self.get_tile_mut(coord).mines = TileMines::Mine;
+
+        for neighbour in self.iter_neighbours(coord) {
+            if let TileMines::NoMine(n) = self.get_tile(neighbour).mines {
+                if n < 8 {
+                    // increment mines variables for tiles around each mine
+                    self.get_tile_mut(neighbour).mines = TileMines::NoMine(n + 1);
                 }
             }
         }
@@ -171,28 +398,32 @@ impl Minesweeper {
     }
 
     pub fn open(&mut self, coord: Coord) {
+        let action = match self.get_tile(coord).state {
+            TileState::Open => Action::Chord(coord),
+            _ => Action::Open(coord),
+        };
+        self.record(action);
+        let mines_left = self.mines_left;
+        self.open_tiles(coord);
+        self.end_move(mines_left);
+    }
+
+    fn open_tiles(&mut self, coord: Coord) {
         // generate the map if it is the first go
         if self.first_go {
-            self.gen_map(coord);
+            if !self.mines_placed {
+                self.gen_map(coord);
+                self.mines_placed = true;
+            }
             self.stopwatch.start();
             self.first_go = false;
         }
         match self.get_tile(coord).state {
-            TileState::Closed => {
-                self.get_tile_mut(coord).state = TileState::Open;
-                if self.get_tile(coord).mines == TileMines::Mine {
-                    self.state = GameState::Lost;
-                } else if self.get_tile(coord).mines == TileMines::NoMine(0) {
-                    // if the tile has no mines around it, open all the tiles around it recursively
-                    for neighbour in self.iter_neighbours(coord) {
-                        if self.get_tile(neighbour).state == TileState::Closed {
-                            self.open(neighbour);
-                        }
-                    }
-                }
-            }
+            TileState::Closed | TileState::Questioned => self.reveal(coord),
+            // opening a flagged tile unflags it, so the flag goes back to the mines left
             TileState::Flagged => {
-                self.get_tile_mut(coord).state = TileState::Closed;
+                self.set_state(coord, TileState::Closed);
+                self.mines_left += 1;
             }
             TileState::Open => {
                 if let TileMines::NoMine(n) = self.get_tile(coord).mines {
@@ -205,8 +436,11 @@ impl Minesweeper {
                     // open all the non-flagged tiles around it (which may open a mine if the flags were wrong)
                     if count == n {
                         for neighbour in self.iter_neighbours(coord) {
-                            if self.get_tile(neighbour).state == TileState::Closed {
-                                self.open(neighbour);
+                            if matches!(
+                                self.get_tile(neighbour).state,
+                                TileState::Closed | TileState::Questioned
+                            ) {
+                                self.reveal(neighbour);
                             }
                         }
                     }
@@ -215,16 +449,60 @@ impl Minesweeper {
         }
     }
 
+    // opens a closed tile, and if the tile has no mines around it, all the tiles around it
+    // (this uses a stack rather than recursion so that huge empty areas can't overflow the call stack)
+    // tiles marked with question marks are opened too, as they are still closed, but flagged tiles aren't
+    fn reveal(&mut self, coord: Coord) {
+        let mut to_open = vec![coord];
+        while let Some(coord) = to_open.pop() {
+            if !matches!(
+                self.get_tile(coord).state,
+                TileState::Closed | TileState::Questioned
+            ) {
+                continue;
+            }
+            self.set_state(coord, TileState::Open);
+            if self.get_tile(coord).mines == TileMines::Mine {
+                self.state = GameState::Lost;
+            } else if self.get_tile(coord).mines == TileMines::NoMine(0) {
+                for neighbour in self.iter_neighbours(coord) {
+                    if matches!(
+                        self.get_tile(neighbour).state,
+                        TileState::Closed | TileState::Questioned
+                    ) {
+                        to_open.push(neighbour);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn flag(&mut self, coord: Coord) {
+        self.record(Action::Flag(coord));
+        let mines_left = self.mines_left;
+        self.flag_tiles(coord);
+        self.end_move(mines_left);
+    }
+
+    fn flag_tiles(&mut self, coord: Coord) {
         match self.get_tile(coord).state {
             TileState::Closed => {
-                self.get_tile_mut(coord).state = TileState::Flagged;
+                self.set_state(coord, TileState::Flagged);
                 self.mines_left -= 1;
             }
+            // with question marks turned on, a flag becomes a question mark before the tile is closed again
             TileState::Flagged => {
-                self.get_tile_mut(coord).state = TileState::Closed;
+                let state = if self.question_marks {
+                    TileState::Questioned
+                } else {
+                    TileState::Closed
+                };
+                self.set_state(coord, state);
                 self.mines_left += 1;
             }
+            TileState::Questioned => {
+                self.set_state(coord, TileState::Closed);
+            }
             TileState::Open => {
                 // if we flag an open tile, either unflag all the tiles around it or flag all the tiles around it
                 // depeding on if all the closed tiles around it are flagged or not (as a shortcut to manually flagging tiles)
@@ -234,7 +512,10 @@ impl Minesweeper {
                     }
                     let mut unflag_all: bool = true;
                     for neighbour in self.iter_neighbours(coord) {
-                        if self.get_tile(neighbour).state == TileState::Closed {
+                        if matches!(
+                            self.get_tile(neighbour).state,
+                            TileState::Closed | TileState::Questioned
+                        ) {
                             // if we find a closed tile that is not flagged around it, don't unflag all the tiles around it
                             unflag_all = false;
                             break;
@@ -245,15 +526,18 @@ impl Minesweeper {
                         for neighbour in self.iter_neighbours(coord) {
                             // if all closed tiles around the tile are flagged, unflag all the closed tiles around it
                             if self.get_tile(neighbour).state == TileState::Flagged {
-                                self.get_tile_mut(neighbour).state = TileState::Closed;
+                                self.set_state(neighbour, TileState::Closed);
                                 self.mines_left += 1;
                             }
                         }
                     } else {
                         for neighbour in self.iter_neighbours(coord) {
-                            // if not, flag all the closed tiles around it
-                            if self.get_tile(neighbour).state == TileState::Closed {
-                                self.get_tile_mut(neighbour).state = TileState::Flagged;
+                            // if not, flag all the closed tiles around it (including any with question marks)
+                            if matches!(
+                                self.get_tile(neighbour).state,
+                                TileState::Closed | TileState::Questioned
+                            ) {
+                                self.set_state(neighbour, TileState::Flagged);
                                 self.mines_left -= 1;
                             }
                         }
@@ -263,6 +547,114 @@ impl Minesweeper {
         }
     }
 
+    fn record(&mut self, action: Action) {
+        self.events.push(ReplayEvent {
+            time_ms: self.stopwatch.elapsed().as_millis() as u64,
+            action,
+        });
+    }
+
+    // changes the state of a tile, remembering the change so the move in progress can be undone
+    fn set_state(&mut self, coord: Coord, state: TileState) {
+        let i = coord.y as usize * self.dimensions.x as usize + coord.x as usize;
+        self.changes.push((i, self.tiles[i].state, state));
+        self.tiles[i].state = state;
+    }
+
+    fn end_move(&mut self, mines_left_before: i64) {
+        // clicks which don't change anything aren't worth undoing
+        if self.changes.is_empty() {
+            return;
+        }
+        self.moves.push(Move {
+            changes: std::mem::take(&mut self.changes),
+            mines_left_before,
+            mines_left_after: self.mines_left,
+        });
+        // a new move replaces any moves which were undone
+        self.undone.clear();
+    }
+
+    // undoes the last open or flag (including everything opened by a chord or flood fill), returning whether
+    // there was anything to undo, and marks the game as practice
+    // the mines stay where they are, even if the first tile opened is undone
+    pub fn undo(&mut self) -> bool {
+        let Some(last) = self.moves.pop() else {
+            return false;
+        };
+        self.record(Action::Undo);
+        for &(i, before, _) in last.changes.iter().rev() {
+            self.tiles[i].state = before;
+        }
+        self.mines_left = last.mines_left_before;
+        self.undone.push(last);
+        self.practice = true;
+        self.check_state();
+        // carry on timing if a lost or won game is back to being played
+        if self.state == GameState::Playing && !self.stopwatch.is_running() {
+            self.stopwatch.start();
+        }
+        true
+    }
+
+    // redoes the last undone move, returning whether there was anything to redo
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.undone.pop() else {
+            return false;
+        };
+        self.record(Action::Redo);
+        for &(i, _, after) in next.changes.iter() {
+            self.tiles[i].state = after;
+        }
+        self.mines_left = next.mines_left_after;
+        self.moves.push(next);
+        self.check_state();
+        true
+    }
+
+    // suggests a tile to open: a definitely safe tile if there is one, or otherwise the tile least likely to be a mine
+    // returns None if the game is over or the chances are too complicated to work out
+    pub fn hint(&mut self) -> Option<Hint> {
+        if self.state != GameState::Playing {
+            return None;
+        }
+        if !self.mines_placed {
+            // the first tile opened is always safe, and the middle is the least likely to open a lone number
+            // (this gives nothing away, so it isn't counted as a hint)
+            return Some(Hint {
+                coord: Coord {
+                    x: self.dimensions.x / 2,
+                    y: self.dimensions.y / 2,
+                },
+                mine_chance: 0.,
+            });
+        }
+        let hint = if let Some(&coord) = solver::solve(self).safe.first() {
+            Some(Hint {
+                coord,
+                mine_chance: 0.,
+            })
+        } else {
+            let probabilities = probability::mine_probabilities(self)?;
+            self.tiles
+                .iter()
+                .filter(|tile| matches!(tile.state, TileState::Closed | TileState::Questioned))
+                .filter_map(|tile| Some((tile.coord, probabilities.get(tile.coord)?)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(coord, mine_chance)| Hint { coord, mine_chance })
+        };
+        if hint.is_some() {
+            self.hints_used += 1;
+        }
+        hint
+    }
+
+    // the 3BV of the board, which is the fewest clicks needed to open every safe tile without flagging or chording
+    // (this is 0 until the mines have been placed)
+    pub fn bbbv(&self) -> u32 {
+        self.bbbv
+    }
+
     // more efficient if we check gamestate once in the main function rather than again and again in open function
     pub fn check_state(&mut self) {
         // check if the game has been won
@@ -287,3 +679,34 @@ impl Minesweeper {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::board;
+
+    #[test]
+    fn undo_and_redo_a_mass_flag() {
+        let mut game = board(&["*.*", "...", "..."]);
+        // opens everything but the top row
+        game.open(Coord { x: 1, y: 2 });
+        let states =
+            |game: &Minesweeper| game.tiles.iter().map(|tile| tile.state).collect::<Vec<_>>();
+        let before = states(&game);
+
+        // flagging the 2 flags all three closed tiles around it, including the safe one
+        game.flag(Coord { x: 1, y: 1 });
+        let flagged = states(&game);
+        assert_eq!(game.mines_left, -1);
+        assert!((0..3).all(|x| game.get_tile(Coord { x, y: 0 }).state == TileState::Flagged));
+
+        assert!(game.undo());
+        assert_eq!(game.mines_left, 2);
+        assert_eq!(states(&game), before);
+
+        assert!(game.redo());
+        assert_eq!(game.mines_left, -1);
+        assert_eq!(states(&game), flagged);
+        assert!(!game.redo());
+    }
+}
@@ -0,0 +1,17 @@
+// the minesweeper engine as a library, completely independent of the graphics
+// the piston frontend in main.rs is built on top of this, and is only compiled with the "gui" feature
+
+pub mod board_file;
+pub mod files;
+pub mod game;
+pub mod metrics;
+pub mod probability;
+pub mod rawvf;
+pub mod replay;
+pub mod save;
+pub mod scores;
+pub mod seed_code;
+pub mod settings;
+pub mod solver;
+pub mod stats;
+pub mod stopwatch;
@@ -2,9 +2,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use piston_window::*;
+use std::time::Duration;
 
-mod game;
-use game::*;
+use minesweeper::game::*;
+use minesweeper::metrics::Metrics;
+use minesweeper::probability::{self, Probabilities};
+use minesweeper::scores::Score;
+use minesweeper::seed_code::SeedCode;
+use minesweeper::settings::Settings;
 
 mod textures;
 use textures::*;
@@ -18,102 +23,731 @@ use rects::*;
 mod consts;
 use consts::*;
 
+mod custom_dialog;
+use custom_dialog::*;
+
+mod board_view;
+use board_view::*;
+
+mod replay_player;
+use replay_player::*;
+
+mod storage;
+use storage::*;
+
+mod resume_dialog;
+use resume_dialog::*;
+
+mod stats_dialog;
+use stats_dialog::*;
+
+mod mouse_input;
+use mouse_input::*;
+
+mod scores_dialog;
+use scores_dialog::*;
+
+mod window_events;
+use window_events::*;
+
+mod dialog;
+
+mod error_dialog;
+use error_dialog::*;
+
 fn main() {
-    let mut game = Minesweeper::new(Difficulty::Easy);
+    // what went wrong with the files the game keeps, or with a file or the clipboard that couldn't be opened,
+    // shown one at a time until a key is pressed or a click
+    let mut errors: Vec<String> = Vec::new();
 
-    let mut window: PistonWindow = WindowSettings::new("Minesweeper", get_window_size(&game))
-        .resizable(false)
+    // the unfinished game from when the window was last closed, which the player is asked whether to carry on
+    // (it is kept until they answer, even if a board or replay was opened from the command line)
+    let mut saved_game = load_saved_game()
+        .map_err(|e| errors.push(e))
+        .unwrap_or_default();
+
+    // the options for new games, no guessing (toggled with G) and question marks (Q), which are kept between runs
+    let mut settings = load_settings()
+        .map_err(|e| errors.push(e))
+        .unwrap_or_default();
+
+    let difficulty = saved_game
+        .as_ref()
+        .map_or(Difficulty::Easy, |game| game.difficulty);
+    let mut game = settings.new_game(difficulty);
+
+    // the replay being watched instead of playing, which can be the current game (R),
+    // or a replay file given on the command line (which can also be a board to play)
+    let mut replay_player = None;
+    match std::env::args().nth(1).map(|path| open_file(&path)) {
+        Some(Ok(OpenedFile::Board(mut imported))) => {
+            imported.question_marks = settings.question_marks;
+            game = imported;
+        }
+        Some(Ok(OpenedFile::Replay(replay))) => replay_player = Some(ReplayPlayer::new(replay)),
+        Some(Err(e)) => errors.push(e),
+        None => {}
+    }
+    let shown_game = replay_player.as_ref().map_or(&game, |player| &player.game);
+    let mut board_view = BoardView::new(shown_game);
+    let mut title = window_title(shown_game, &settings, replay_player.is_some());
+
+    // the size of the window before it is scaled, which everything in it is laid out in
+    let mut layout_size = get_window_size(&board_view);
+
+    let mut window: PistonWindow = WindowSettings::new(title.clone(), layout_size)
+        .resizable(true)
         .fullscreen(false)
         .exit_on_esc(true)
         .build()
         .unwrap();
 
-    let mut face_button_rect = get_face_button_rect(&window);
-    let mut difficulty_buttons_rects = get_difficulty_buttons_rects(&window);
+    let mut face_button_rect = get_face_button_rect(layout_size);
+    let mut hint_button_rect = get_hint_button_rect(layout_size);
+    let mut difficulty_buttons_rects = get_difficulty_buttons_rects(layout_size);
 
     let textures = GameTextures::new(&mut window);
+    let mut glyphs = load_glyphs(&mut window);
+
+    // the dialog for entering a custom board, if it is open
+    let mut custom_dialog: Option<CustomDialog> = None;
+
+    // the statistics of finished games, and the dialog showing them if it is open (S)
+    let mut statistics = load_statistics()
+        .map_err(|e| errors.push(e))
+        .unwrap_or_default();
+    let mut stats_dialog: Option<StatsDialog> = None;
+    // the fastest wins, and the dialog showing them (B) or asking for a name after a new high score
+    let mut high_scores = load_high_scores()
+        .map_err(|e| errors.push(e))
+        .unwrap_or_default();
+    let mut scores_dialog: Option<ScoresDialog> = None;
+
+    // the mouse position in the window, and where that is in the layout once the window's scale is undone
+    let mut window_mouse_pos = [0., 0.];
+    let mut mouse_pos;
+
+    // whether shift or ctrl are held, which change what the mouse wheel does
+    let mut shift_held = false;
+    let mut ctrl_held = false;
 
-    let mut mouse_pos = [0., 0.];
+    // how many decimal places of a second the stopwatch shows, changed with the . key
+    let mut stopwatch_decimals = 0;
 
-    // the tile that the mouse is currently over
-    let mut coord: Option<Coord> = None;
+    // whether closed tiles are coloured by their chance of being a mine, toggled with M
+    let mut show_heat_map = false;
+    // the chances for the board as it was when they were last worked out, which only changes when tiles are opened
+    // (or closed again by undoing), so they are kept with what could be seen of the board then
+    let mut probabilities: Option<Probabilities> = None;
+    let mut probabilities_board = None;
 
-    window.set_max_fps(8);
+    // the tile suggested by the last hint (T or the hint button), until the board changes
+    let mut hint: Option<Hint> = None;
+
+    // the mouse buttons held down on the board
+    let mut mouse_input = MouseInput::default();
+
+    // whether the game is paused (P, or when the window loses focus), which stops the stopwatch and hides the board
+    let mut paused = false;
+
+    // the tile selected with the keyboard, which is shown once it has been moved with the arrow keys or hjkl
+    let mut cursor: Option<Coord> = None;
+
+    // the window is only drawn when something in it changes
+    let mut events = WindowEvents::new();
+    // the tile the mouse was over, as moving onto another tile can change which tiles are shown pressed in
+    let mut hovered = None;
 
     // the main game loop
-    while let Some(e) = window.next() {
+    while let Some(e) = events.next(&mut window) {
         e.mouse_cursor(|pos| {
-            mouse_pos = pos;
-            // if the mouse is over the game area, set the coord to the tile that the mouse is over
-            if mouse_intersects_rect(
-                mouse_pos,
-                [
-                    BORDER_SIZE,
-                    BORDER_SIZE + BORDER_MIDDLE_POS,
-                    (game.dimensions.x as f64) * TILE_SIZE,
-                    (game.dimensions.y as f64) * TILE_SIZE,
-                ],
-            ) {
-                coord = Some(Coord {
-                    x: ((pos[0] - BORDER_SIZE) / TILE_SIZE) as u8,
-                    y: ((pos[1] - BORDER_MIDDLE_POS - BORDER_SIZE) / TILE_SIZE) as u8,
-                });
+            window_mouse_pos = pos;
+        });
+        // the layout is scaled to fit the window, which can be resized or zoomed
+        let window_size = [window.size().width, window.size().height];
+        let view_rect = get_view_rect(window_size, layout_size);
+        let scale = view_rect[2] / layout_size[0];
+        mouse_pos = [
+            (window_mouse_pos[0] - view_rect[0]) / scale,
+            (window_mouse_pos[1] - view_rect[1]) / scale,
+        ];
+        // an error is closed by the next key press or click, which does nothing else
+        if !errors.is_empty() && e.press_args().is_some() {
+            errors.remove(0);
+            continue;
+        }
+
+        // the scale the window is set to by the zoom keys, or kept at when the board changes size
+        let mut new_scale = None;
+
+        // whether the board has changed size, so the window needs resizing
+        let mut resize = false;
+        // how far the keyboard cursor was moved
+        let mut cursor_move = None;
+        // a replay is saved whenever a game finishes
+        let state_before = game.state;
+
+        // while a name is being typed, letters shouldn't also do what their keys normally do
+        let typing = custom_dialog.is_some()
+            || scores_dialog
+                .as_ref()
+                .is_some_and(|dialog| dialog.entering_name());
+
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            match key {
+                Key::LShift | Key::RShift => shift_held = true,
+                Key::LCtrl | Key::RCtrl => ctrl_held = true,
+                // ctrl+= and ctrl+- zoom the whole window, and ctrl+0 puts it back to its normal size
+                Key::Equals | Key::Plus | Key::NumPadPlus if ctrl_held => {
+                    new_scale = Some(scale * ZOOM_SPEED)
+                }
+                Key::Minus | Key::NumPadMinus if ctrl_held => new_scale = Some(scale / ZOOM_SPEED),
+                Key::D0 | Key::NumPad0 if ctrl_held => new_scale = Some(1.),
+                // R starts watching a replay of the current game, or stops watching a replay
+                // (but a replay isn't started while paused, as it would show the hidden board)
+                Key::R
+                    if !typing && saved_game.is_none() && (replay_player.is_some() || !paused) =>
+                {
+                    // the game can't be played while its replay is watched, so it isn't timed either
+                    replay_player = match replay_player {
+                        Some(_) => {
+                            if game.started() && game.state == GameState::Playing && !paused {
+                                game.stopwatch.start();
+                            }
+                            None
+                        }
+                        None => {
+                            game.stopwatch.stop();
+                            Some(ReplayPlayer::new(game.replay()))
+                        }
+                    };
+                    resize = true;
+                }
+                Key::S if !typing && saved_game.is_none() => {
+                    stats_dialog = match stats_dialog {
+                        Some(_) => None,
+                        None => Some(StatsDialog::new(&statistics, game.difficulty)),
+                    };
+                    scores_dialog = None;
+                }
+                // P pauses a game which has started, or carries on with a paused game
+                Key::P if !typing && saved_game.is_none() => {
+                    if paused {
+                        game.stopwatch.start();
+                        paused = false;
+                    } else if game.stopwatch.is_running() {
+                        game.stopwatch.stop();
+                        paused = true;
+                    }
+                }
+                Key::B if !typing && saved_game.is_none() => {
+                    scores_dialog = match scores_dialog {
+                        Some(_) => None,
+                        None => Some(ScoresDialog::new(&high_scores, game.difficulty)),
+                    };
+                    stats_dialog = None;
+                }
+                // the rest of the keys are for playing, so do nothing while a dialog is open or a replay is being watched
+                _ if board_covered(
+                    &custom_dialog,
+                    &stats_dialog,
+                    &scores_dialog,
+                    &replay_player,
+                    saved_game.is_some(),
+                    paused,
+                ) => {}
+                // C copies a code for the current board (its difficulty, seed and whether it is made without
+                // guessing) so it can be shared, and V starts a new game from a code, a seed or a board (as text)
+                // in the clipboard
+                Key::C => {
+                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                        let _ = clipboard.set_text(SeedCode::of(&game).to_string());
+                    }
+                }
+                // G and Q turn no guessing and question marks on or off for the next game,
+                // so the game in progress isn't lost
+                Key::G => {
+                    settings.no_guess = !settings.no_guess;
+                    errors.extend(save_settings(&settings).err());
+                }
+                Key::Q => {
+                    settings.question_marks = !settings.question_marks;
+                    errors.extend(save_settings(&settings).err());
+                }
+                // E saves the board to a file and copies it to the clipboard, once the mines have been placed
+                Key::E => {
+                    if let Some(text) = game.board_text() {
+                        errors.extend(export_board(&game).err());
+                        if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                            let _ = clipboard.set_text(text);
+                        }
+                    }
+                }
+                Key::M => show_heat_map = !show_heat_map,
+                // . shows whole seconds, tenths or hundredths on the stopwatch
+                Key::Period => stopwatch_decimals = (stopwatch_decimals + 1) % 3,
+                Key::T => hint = game.hint(),
+                // ctrl+z and ctrl+y undo and redo moves, which also works after losing
+                Key::Z if ctrl_held => {
+                    game.undo();
+                    hint = None;
+                }
+                Key::Y if ctrl_held => {
+                    game.redo();
+                    hint = None;
+                }
+                // the keyboard controls, which move a cursor around the board instead of the mouse
+                Key::Left | Key::H => cursor_move = Some((-1, 0)),
+                Key::Right | Key::L => cursor_move = Some((1, 0)),
+                Key::Up | Key::K => cursor_move = Some((0, -1)),
+                Key::Down | Key::J => cursor_move = Some((0, 1)),
+                Key::Space | Key::F | Key::D if game.state == GameState::Playing => {
+                    if let Some(coord) = cursor {
+                        let open = game.get_tile(coord).state == TileState::Open;
+                        match key {
+                            Key::F => game.flag(coord),
+                            // D only chords, and space only opens, so a number can't be chorded by mistake
+                            Key::D if open => game.open(coord),
+                            Key::Space if !open => game.open(coord),
+                            _ => {}
+                        }
+                        game.check_state();
+                        hint = None;
+                    }
+                }
+                Key::N => game = settings.new_game(game.difficulty),
+                Key::D1 | Key::D2 | Key::D3 => {
+                    let difficulty = match key {
+                        Key::D1 => Difficulty::Easy,
+                        Key::D2 => Difficulty::Medium,
+                        _ => Difficulty::Hard,
+                    };
+                    game = settings.new_game(difficulty);
+                    resize = true;
+                }
+                Key::V => {
+                    let clipboard_text =
+                        arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text());
+                    let clipboard_text = clipboard_text.unwrap_or_default();
+                    // (custom boards from codes have the same limits as ones entered in the window)
+                    let code = SeedCode::parse(&clipboard_text).filter(|code| {
+                        let (size, _) = code.difficulty.board_info();
+                        let size_range = CUSTOM_MIN_SIZE..=CUSTOM_MAX_SIZE;
+                        size_range.contains(&size.x) && size_range.contains(&size.y)
+                    });
+                    if let Some(code) = code {
+                        game = code.new_game();
+                        game.question_marks = settings.question_marks;
+                        resize = true;
+                    } else if let Ok(seed) = clipboard_text.trim().parse() {
+                        // a seed on its own is played with the current difficulty and settings
+                        game = settings.game_with_seed(game.difficulty, seed);
+                    } else {
+                        match Minesweeper::from_board_text(&clipboard_text) {
+                            Ok(mut imported) => {
+                                imported.question_marks = settings.question_marks;
+                                game = imported;
+                                resize = true;
+                            }
+                            Err(e) => errors.push(format!("couldn't open the clipboard: {e}")),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        // the game pauses itself when the player switches to another window, so it isn't timed while they are away
+        if e.focus_args() == Some(false) && game.stopwatch.is_running() {
+            game.stopwatch.stop();
+            paused = true;
+        }
+
+        if let Some(Button::Keyboard(key)) = e.release_args() {
+            match key {
+                Key::LShift | Key::RShift => shift_held = false,
+                Key::LCtrl | Key::RCtrl => ctrl_held = false,
+                _ => {}
+            }
+        }
+
+        // the mouse wheel scrolls the board, or zooms it if ctrl is held
+        if let Some([dx, dy]) = e.mouse_scroll_args() {
+            if ctrl_held {
+                board_view.zoom(ZOOM_SPEED.powf(dy), mouse_pos);
+            } else if shift_held {
+                board_view.scroll(-dy * SCROLL_SPEED, 0.);
             } else {
-                coord = None;
+                board_view.scroll(-dx * SCROLL_SPEED, -dy * SCROLL_SPEED);
             }
-        });
+        }
+
+        if let Some(player) = &mut replay_player {
+            if let Some(args) = e.update_args() {
+                player.update(args.dt);
+            }
+            if let Some(Button::Keyboard(key)) = e.press_args() {
+                match key {
+                    Key::Space => player.toggle_playing(),
+                    Key::Left => player.step_back(),
+                    Key::Right => player.step_forward(),
+                    Key::Up => player.faster(),
+                    Key::Down => player.slower(),
+                    Key::Home => player.restart(),
+                    _ => {}
+                }
+            }
+        }
+
+        // the tile that the mouse is currently over
+        let coord = board_view.coord_at(mouse_pos);
+
+        if let Some(dialog) = &mut custom_dialog {
+            if let Some(text) = e.text_args() {
+                dialog.type_text(&text);
+            }
+            if let Some(Button::Keyboard(key)) = e.press_args() {
+                match key {
+                    Key::Backspace => dialog.backspace(),
+                    Key::Tab => dialog.next_field(),
+                    Key::Return | Key::NumPadEnter => {
+                        if let Some(difficulty) = dialog.submit() {
+                            game = settings.new_game(difficulty);
+                            custom_dialog = None;
+                            replay_player = None;
+                            resize = true;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(dialog) = &mut stats_dialog {
+            if let Some(Button::Keyboard(key)) = e.press_args() {
+                match key {
+                    Key::Tab => dialog.next(),
+                    Key::Delete if dialog.reset(&mut statistics) => {
+                        errors.extend(save_statistics(&statistics).err())
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(dialog) = &mut scores_dialog {
+            if let Some(text) = e.text_args() {
+                dialog.type_text(&text);
+            }
+            if let Some(Button::Keyboard(key)) = e.press_args() {
+                match key {
+                    Key::Backspace => dialog.backspace(),
+                    Key::Tab => dialog.next(),
+                    Key::Return | Key::NumPadEnter if dialog.submit(&mut high_scores) => {
+                        errors.extend(save_high_scores(&high_scores).err())
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // whether to resume the saved game or start a new one, once the player has chosen
+        let mut resume = None;
+        if saved_game.is_some() {
+            if let Some(Button::Keyboard(key)) = e.press_args() {
+                match key {
+                    Key::Return | Key::NumPadEnter => resume = Some(true),
+                    Key::N => resume = Some(false),
+                    _ => {}
+                }
+            }
+        }
 
         if let Some(Button::Mouse(button)) = e.press_args() {
-            if button == MouseButton::Left {
+            if button == MouseButton::Left && saved_game.is_some() {
+                resume = resume_dialog_click(mouse_pos, get_dialog_rect(&board_view));
+            } else if button == MouseButton::Left {
                 if mouse_intersects_rect(mouse_pos, face_button_rect) {
-                    game = game::Minesweeper::new(game.difficulty);
-                } else {
-                    for (i, button_rect) in difficulty_buttons_rects.iter().enumerate() {
-                        if mouse_intersects_rect(mouse_pos, *button_rect) {
-                            game = match i {
-                                1 => game::Minesweeper::new(Difficulty::Medium),
-                                2 => game::Minesweeper::new(Difficulty::Hard),
-                                _ => game::Minesweeper::new(Difficulty::Easy),
-                            };
-                        }
+                    game = settings.new_game(game.difficulty);
+                    custom_dialog = None;
+                    // the replay may have been a different size to the game
+                    if replay_player.take().is_some() {
+                        resize = true;
+                    }
+                } else if let Some(dialog) = &mut custom_dialog {
+                    dialog.click(mouse_pos, get_dialog_rect(&board_view));
+                } else if let Some(player) = &mut replay_player {
+                    let bar_rect = get_replay_bar_rect(layout_size);
+                    if mouse_intersects_rect(mouse_pos, bar_rect) {
+                        player.seek((mouse_pos[0] - bar_rect[0]) / bar_rect[2]);
+                    }
+                } else if mouse_intersects_rect(mouse_pos, hint_button_rect)
+                    && !board_covered(
+                        &custom_dialog,
+                        &stats_dialog,
+                        &scores_dialog,
+                        &replay_player,
+                        saved_game.is_some(),
+                        paused,
+                    )
+                {
+                    hint = game.hint();
+                }
+                for (i, button_rect) in difficulty_buttons_rects.into_iter().enumerate() {
+                    if mouse_intersects_rect(mouse_pos, button_rect) {
+                        match i {
+                            1 => game = settings.new_game(Difficulty::Medium),
+                            2 => game = settings.new_game(Difficulty::Hard),
+                            // the custom button toggles the dialog rather than starting a new game
+                            3 => {
+                                custom_dialog = match custom_dialog {
+                                    Some(_) => None,
+                                    None => Some(CustomDialog::new(game.difficulty)),
+                                };
+                                stats_dialog = None;
+                                scores_dialog = None;
+                                continue;
+                            }
+                            _ => game = settings.new_game(Difficulty::Easy),
+                        };
+                        custom_dialog = None;
+                        replay_player = None;
+                        resize = true;
                     }
-                    window.set_size(get_window_size(&game));
-                    face_button_rect = get_face_button_rect(&window);
-                    difficulty_buttons_rects = get_difficulty_buttons_rects(&window);
                 }
             }
-            if game.state == GameState::Playing {
-                if let Some(coord) = coord {
-                    if button == MouseButton::Left {
-                        game.open(coord);
-                    } else if button == MouseButton::Right {
-                        game.flag(coord);
+        }
+
+        let playable = game.state == GameState::Playing
+            && !board_covered(
+                &custom_dialog,
+                &stats_dialog,
+                &scores_dialog,
+                &replay_player,
+                saved_game.is_some(),
+                paused,
+            );
+        // tiles are opened when the button is released, but flagged as soon as it is pressed
+        if let Some(Button::Mouse(button)) = e.press_args() {
+            if let Some(coord) = coord.filter(|_| playable) {
+                hint = None;
+                // the keyboard cursor is hidden when the mouse is used, until it is moved again
+                cursor = None;
+                if let Some(action) = mouse_input.press(button, game.get_tile(coord).state) {
+                    action.apply(&mut game, coord);
+                }
+            }
+        }
+        if let Some(Button::Mouse(button)) = e.release_args() {
+            if let (Some(action), Some(coord), true) =
+                (mouse_input.release(button), coord, playable)
+            {
+                action.apply(&mut game, coord);
+            }
+        }
+        if !playable {
+            mouse_input.cancel();
+        }
+
+        match resume {
+            Some(true) => {
+                if let Some(saved) = saved_game.take() {
+                    game = saved;
+                    // the stopwatch was stopped when the game was saved, and it stays stopped while a replay is
+                    // watched, until the replay is closed
+                    if game.started() && replay_player.is_none() {
+                        game.stopwatch.start();
                     }
-                    game.check_state();
+                    resize = true;
                 }
             }
+            // the new game (or the board opened from the command line) is already set up
+            Some(false) => saved_game = None,
+            None => {}
         }
 
-        let window_size = window.size();
+        if state_before == GameState::Playing && game.state != GameState::Playing {
+            errors.extend(save_replay(&game.replay()).err());
+            if statistics.record(&game) {
+                errors.extend(save_statistics(&statistics).err());
+            }
+            // ask for the player's name if the win is fast enough to get in the high scores
+            if game.state == GameState::Won && game.counts_towards_records() {
+                let score = Score::new(&game, String::new(), unix_time());
+                if high_scores.rank(game.difficulty, &score).is_some() {
+                    scores_dialog = Some(ScoresDialog::new_score(
+                        &high_scores,
+                        game.difficulty,
+                        score,
+                    ));
+                    stats_dialog = None;
+                }
+            }
+        }
+
+        // a new game (from the face or difficulty buttons) isn't paused
+        paused &= game.started() && game.state == GameState::Playing;
+
+        // the game shown in the window, which is the game being replayed while watching a replay
+        let shown_game = replay_player.as_ref().map_or(&game, |player| &player.game);
+
+        if resize {
+            board_view = BoardView::new(shown_game);
+            layout_size = get_window_size(&board_view);
+            face_button_rect = get_face_button_rect(layout_size);
+            hint_button_rect = get_hint_button_rect(layout_size);
+            difficulty_buttons_rects = get_difficulty_buttons_rects(layout_size);
+            new_scale = new_scale.or(Some(scale));
+        }
+        if let Some(new_scale) = new_scale {
+            let new_scale = new_scale.clamp(MIN_WINDOW_SCALE, MAX_WINDOW_SCALE);
+            window.set_size([layout_size[0] * new_scale, layout_size[1] * new_scale]);
+        }
+
+        // the cursor starts in the middle of the board, and can't go off its edges
+        if let Some((dx, dy)) = cursor_move {
+            let moved = match cursor {
+                Some(coord) => Coord {
+                    x: coord.x.saturating_add_signed(dx),
+                    y: coord.y.saturating_add_signed(dy),
+                },
+                None => Coord {
+                    x: game.dimensions.x / 2,
+                    y: game.dimensions.y / 2,
+                },
+            };
+            let clamped = Coord {
+                x: moved.x.min(game.dimensions.x - 1),
+                y: moved.y.min(game.dimensions.y - 1),
+            };
+            board_view.scroll_to(clamped);
+            cursor = Some(clamped);
+        }
+        // a new board may be smaller than the last one
+        cursor = cursor.filter(|coord| coord.x < game.dimensions.x && coord.y < game.dimensions.y);
 
-        let border_sprites = border_sprites(&window_size, &textures);
+        if window_title(shown_game, &settings, replay_player.is_some()) != title {
+            title = window_title(shown_game, &settings, replay_player.is_some());
+            window.set_title(title.clone());
+            hint = None;
+        }
+
+        if show_heat_map && game.state == GameState::Playing {
+            // everything the chances are worked out from: the size of the board, the number of mines,
+            // and the number on each open tile
+            let board = (
+                game.dimensions,
+                game.mines(),
+                game.tiles
+                    .iter()
+                    .map(|tile| match (tile.state, &tile.mines) {
+                        (TileState::Open, &TileMines::NoMine(n)) => Some(n),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            if probabilities_board.as_ref() != Some(&board) {
+                probabilities = probability::mine_probabilities(&game);
+                probabilities_board = Some(board);
+            }
+        }
+        let heat_map = probabilities.as_ref().filter(|_| {
+            show_heat_map && game.state == GameState::Playing && replay_player.is_none()
+        });
+
+        let border_sprites = border_sprites(layout_size, &textures);
         let difficulty_buttons_sprites =
-            difficulty_buttons_sprites(&game, &textures, difficulty_buttons_rects);
-        let stopwatch_sprites = stopwatch_sprites(&game, &textures, get_stopwatch_rect(&window));
-        let mines_left_sprites = mines_left_sprites(&game, &textures, get_mines_left_rect());
+            difficulty_buttons_sprites(shown_game, &textures, difficulty_buttons_rects);
+        let time = match &replay_player {
+            Some(player) => player.time(),
+            None => game.stopwatch.elapsed(),
+        };
+        // wake up to draw the stopwatch when the time it shows changes, or the next frame of a replay while it plays
+        events.wake_after(match &replay_player {
+            Some(player) => player.next_frame(),
+            None => game
+                .stopwatch
+                .is_running()
+                .then(|| until_next_tick(time, stopwatch_decimals)),
+        });
+        let stopwatch_text = stopwatch_text(time, stopwatch_decimals);
+        let stopwatch_sprites = num_display_sprites(
+            &textures,
+            get_stopwatch_rect(layout_size, &stopwatch_text),
+            &stopwatch_text,
+        );
+        let mines_left_text = mines_left_text(shown_game);
+        let mines_left_sprites = num_display_sprites(
+            &textures,
+            get_mines_left_rect(layout_size, &mines_left_text),
+            &mines_left_text,
+        );
+        let replay_bar_rect = get_replay_bar_rect(layout_size);
+        // how well the game was played, shown once it is over
+        // (using the time on the stopwatch display, as a replay's game is timed by how fast it is played back)
+        let metrics = (shown_game.state != GameState::Playing).then(|| Metrics {
+            time_ms: time.as_millis() as u64,
+            ..shown_game.metrics()
+        });
+
+        let dialog_rect = get_dialog_rect(&board_view);
+        // the tiles shown pressed in while a mouse button is held over them
+        let pressed = mouse_input.pressed_tiles(&game, board_view.coord_at(mouse_pos));
+        if board_view.coord_at(mouse_pos) != hovered {
+            hovered = board_view.coord_at(mouse_pos);
+            events.redraw();
+        }
 
-        window.draw_2d(&e, |c, g, _| {
+        // the view may have changed if the window was resized by the last event
+        let view_rect = get_view_rect([window.size().width, window.size().height], layout_size);
+        let scale = view_rect[2] / layout_size[0];
+
+        window.draw_2d(&e, |c, g, device| {
             clear([0.75; 4], g);
-            // draw the tiles
-            for (image, texture) in tile_sprites(&game, &textures) {
-                image.draw(texture, &Default::default(), c.transform, g);
+            // everything is drawn in the layout's coordinates, and scaled to fit the window
+            let c = c.trans(view_rect[0], view_rect[1]).scale(scale, scale);
+            // draw the tiles, clipped to the visible area of the board
+            // (the scissor rect is in pixels of the framebuffer, which can differ from the window size)
+            let pixels = c
+                .viewport
+                .map(|v| v.draw_size[0] as f64 / v.window_size[0])
+                .unwrap_or(1.);
+            let board_draw_state = DrawState::default().scissor([
+                ((view_rect[0] + board_view.rect[0] * scale) * pixels) as u32,
+                ((view_rect[1] + board_view.rect[1] * scale) * pixels) as u32,
+                (board_view.rect[2] * scale * pixels) as u32,
+                (board_view.rect[3] * scale * pixels) as u32,
+            ]);
+            for (image, texture) in
+                tile_sprites(shown_game, &textures, &board_view, heat_map, &pressed)
+            {
+                image.draw(texture, &board_draw_state, c.transform, g);
+            }
+            if let Some(hint) = hint {
+                draw_hint(hint, &board_view, &board_draw_state, c, g, &mut glyphs);
+                glyphs.factory.encoder.flush(device);
+            }
+            if let Some(coord) = cursor.filter(|_| replay_player.is_none()) {
+                draw_cursor(coord, &board_view, &board_draw_state, c, g);
+            }
+            if let Some(metrics) = metrics {
+                draw_metrics(metrics, board_view.rect, c, g, &mut glyphs);
+                glyphs.factory.encoder.flush(device);
+            }
+            // the board is hidden while paused, so it can't be studied while the stopwatch is stopped
+            if paused && replay_player.is_none() {
+                draw_paused(board_view.rect, c, g, &mut glyphs);
+                glyphs.factory.encoder.flush(device);
             }
             // draw the border
             for (image, texture) in border_sprites {
                 image.draw(texture, &Default::default(), c.transform, g);
             }
             // draw the face button
-            let (image, texture) = face_button_sprite(&game, &textures, face_button_rect);
+            let (image, texture) =
+                face_button_sprite(shown_game, &textures, face_button_rect, mouse_input.held());
+            image.draw(texture, &Default::default(), c.transform, g);
+            // draw the hint button
+            let (image, texture) = hint_button_sprite(hint, &textures, hint_button_rect);
             image.draw(texture, &Default::default(), c.transform, g);
             // draw the difficulty buttons
             for (image, texture) in difficulty_buttons_sprites {
@@ -127,6 +761,239 @@ fn main() {
             for (image, texture) in mines_left_sprites {
                 image.draw(texture, &Default::default(), c.transform, g);
             }
+            // draw the replay controls over the board and border
+            if let Some(player) = &replay_player {
+                player.draw(replay_bar_rect, board_view.rect, c, g, &mut glyphs);
+                glyphs.factory.encoder.flush(device);
+            }
+            // draw the custom board dialog over the board
+            if let Some(dialog) = &custom_dialog {
+                dialog.draw(dialog_rect, c, g, &mut glyphs);
+                glyphs.factory.encoder.flush(device);
+            }
+            if let Some(dialog) = &stats_dialog {
+                dialog.draw(&statistics, dialog_rect, c, g, &mut glyphs);
+                glyphs.factory.encoder.flush(device);
+            }
+            if let Some(dialog) = &scores_dialog {
+                dialog.draw(&high_scores, dialog_rect, c, g, &mut glyphs);
+                glyphs.factory.encoder.flush(device);
+            }
+            // the saved game is hidden until the player chooses to resume it, as its stopwatch isn't running
+            if let Some(saved) = &saved_game {
+                rectangle([0.75; 4], board_view.rect, c.transform, g);
+                draw_resume_dialog(saved, dialog_rect, c, g, &mut glyphs);
+                glyphs.factory.encoder.flush(device);
+            }
+            // errors are drawn over everything else, as they are about what the player just tried to do
+            if let Some(message) = errors.first() {
+                draw_error_dialog(message, dialog_rect, c, g, &mut glyphs);
+                glyphs.factory.encoder.flush(device);
+            }
         });
     }
+
+    // the window has been closed, so save the game to carry on next time
+    // (unless the player never answered whether to resume the saved game, which is then kept for next time)
+    // (there is no window left to show an error in)
+    if saved_game.is_none() {
+        if let Err(e) = save_game(&game) {
+            eprintln!("{e}");
+        }
+    }
+}
+
+// how long until the stopwatch shows a different time, when it shows a number of decimal places of a second
+fn until_next_tick(time: Duration, decimals: u32) -> Duration {
+    let tick = 1_000_000_000 / 10u64.pow(decimals);
+    Duration::from_nanos(tick - (time.as_nanos() % tick as u128) as u64)
+}
+
+// whether the board can't be played because something is covering it: a dialog, a replay being watched,
+// the question of whether to resume the saved game, or the pause screen
+// (the keys and buttons for playing, like T and the hint button, do nothing then too)
+fn board_covered(
+    custom_dialog: &Option<CustomDialog>,
+    stats_dialog: &Option<StatsDialog>,
+    scores_dialog: &Option<ScoresDialog>,
+    replay_player: &Option<ReplayPlayer>,
+    offer_resume: bool,
+    paused: bool,
+) -> bool {
+    custom_dialog.is_some()
+        || stats_dialog.is_some()
+        || scores_dialog.is_some()
+        || replay_player.is_some()
+        || offer_resume
+        || paused
+}
+
+fn window_title(game: &Minesweeper, settings: &Settings, replay: bool) -> String {
+    let mut info = format!("seed {}", game.seed);
+    if replay {
+        info = format!("replay, {info}");
+    }
+    if game.no_guess {
+        info.push_str(", no guessing");
+    }
+    if game.question_marks {
+        info.push_str(", question marks");
+    }
+    // games where a move was undone don't count, so make it clear
+    if game.practice {
+        info.push_str(", practice");
+    }
+    // settings which have been changed only apply to the next game, so say so until then
+    if !replay {
+        if settings.no_guess != game.no_guess {
+            info.push_str(if settings.no_guess {
+                ", next game: no guessing"
+            } else {
+                ", next game: guessing allowed"
+            });
+        }
+        if settings.question_marks != game.question_marks {
+            info.push_str(if settings.question_marks {
+                ", next game: question marks"
+            } else {
+                ", next game: no question marks"
+            });
+        }
+    }
+    format!("Minesweeper ({info})")
+}
+
+// outlines the hinted tile, and shows its chance of being a mine if it isn't definitely safe
+fn draw_hint(
+    hint: Hint,
+    board_view: &BoardView,
+    draw_state: &DrawState,
+    c: Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+) {
+    let rect = board_view.tile_rect(hint.coord);
+    let border_width = f64::max(board_view.tile_size / 16., 1.);
+    Rectangle::new_border([1., 0.8, 0., 1.], border_width).draw(
+        [
+            rect[0] + border_width,
+            rect[1] + border_width,
+            rect[2] - border_width * 2.,
+            rect[3] - border_width * 2.,
+        ],
+        draw_state,
+        c.transform,
+        g,
+    );
+    if hint.mine_chance > 0. {
+        let text_size = (board_view.tile_size * 0.3) as u32;
+        text::Text::new_color([0.8, 0., 0., 1.], text_size)
+            .draw(
+                // (small chances have a decimal place, so they don't look like 0%)
+                &if hint.mine_chance < 0.01 {
+                    format!("{:.1}%", hint.mine_chance * 100.)
+                } else {
+                    format!("{:.0}%", hint.mine_chance * 100.)
+                },
+                glyphs,
+                draw_state,
+                c.transform.trans(
+                    rect[0] + border_width * 2.,
+                    rect[1] + rect[3] / 2. + text_size as f64 / 2.,
+                ),
+                g,
+            )
+            .unwrap();
+    }
+}
+
+// shows the exact time, 3BV, 3BV/s, efficiency and how much of the board was cleared in the bottom corner of the board
+fn draw_metrics(
+    metrics: Metrics,
+    board_rect: [f64; 4],
+    c: Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+) {
+    let lines = [
+        format!("Time {:.3}s", metrics.time_ms as f64 / 1000.),
+        format!(
+            "3BV {}/{} ({:.0}%)",
+            metrics.bbbv_solved,
+            metrics.bbbv,
+            metrics.completion() * 100.
+        ),
+        format!(
+            "3BV/s {:.2}, IOE {:.2}, {} clicks",
+            metrics.bbbv_per_second(),
+            metrics.ioe(),
+            metrics.clicks.total()
+        ),
+    ];
+    let rect = [
+        board_rect[0],
+        board_rect[1] + board_rect[3] - 54.,
+        f64::min(board_rect[2], 240.),
+        54.,
+    ];
+    rectangle([0., 0., 0., 0.6], rect, c.transform, g);
+    for (i, line) in lines.iter().enumerate() {
+        text::Text::new_color([1.; 4], TEXT_SIZE - 4)
+            .draw(
+                line,
+                glyphs,
+                &c.draw_state,
+                c.transform
+                    .trans(rect[0] + 6., rect[1] + 16. + i as f64 * 16.),
+                g,
+            )
+            .unwrap();
+    }
+}
+
+// covers the board while the game is paused, saying how to carry on
+fn draw_paused(board_rect: [f64; 4], c: Context, g: &mut G2d, glyphs: &mut Glyphs) {
+    rectangle([0.75; 4], board_rect, c.transform, g);
+    let lines = [
+        ("Paused", TEXT_SIZE + 4),
+        ("Press P to carry on", TEXT_SIZE - 4),
+    ];
+    for (i, (line, size)) in lines.into_iter().enumerate() {
+        let width = glyphs.width(size, line).unwrap_or(0.);
+        text::Text::new_color(dialog::DIALOG_TEXT_COLOR, size)
+            .draw(
+                line,
+                glyphs,
+                &c.draw_state,
+                c.transform.trans(
+                    board_rect[0] + (board_rect[2] - width) / 2.,
+                    board_rect[1] + board_rect[3] / 2. + i as f64 * 28.,
+                ),
+                g,
+            )
+            .unwrap();
+    }
+}
+
+// outlines the tile selected with the keyboard
+fn draw_cursor(
+    coord: Coord,
+    board_view: &BoardView,
+    draw_state: &DrawState,
+    c: Context,
+    g: &mut G2d,
+) {
+    let rect = board_view.tile_rect(coord);
+    let border_width = f64::max(board_view.tile_size / 12., 1.);
+    Rectangle::new_border([0., 0.3, 1., 1.], border_width).draw(
+        [
+            rect[0] + border_width,
+            rect[1] + border_width,
+            rect[2] - border_width * 2.,
+            rect[3] - border_width * 2.,
+        ],
+        draw_state,
+        c.transform,
+        g,
+    );
 }
@@ -0,0 +1,129 @@
+// the standard measures of how well a game was played, which players use to compare games with each other
+
+use crate::game::*;
+use crate::replay::Action;
+use crate::solver;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Clicks {
+    // opening a closed tile (or unflagging one by opening it)
+    pub left: u32,
+    pub right: u32,
+    // opening around an open number
+    pub chord: u32,
+}
+impl Clicks {
+    pub fn total(&self) -> u32 {
+        self.left + self.right + self.chord
+    }
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Metrics {
+    // the fewest clicks needed to open every safe tile, and how many of those clicks' worth of the board is open
+    pub bbbv: u32,
+    pub bbbv_solved: u32,
+    pub clicks: Clicks,
+    pub time_ms: u64,
+}
+impl Metrics {
+    pub fn bbbv_per_second(&self) -> f64 {
+        // a game finished in under a millisecond would otherwise divide by 0
+        self.bbbv_solved as f64 * 1000. / self.time_ms.max(1) as f64
+    }
+
+    // the efficiency: how much of the board each click opened, which is 1 for a game played in the fewest clicks
+    // (and can be more than 1 by chording)
+    pub fn ioe(&self) -> f64 {
+        match self.clicks.total() {
+            0 => 0.,
+            clicks => self.bbbv_solved as f64 / clicks as f64,
+        }
+    }
+
+    // the fraction of the board that was cleared, which is 1 for a win
+    pub fn completion(&self) -> f64 {
+        match self.bbbv {
+            0 => 1.,
+            bbbv => self.bbbv_solved as f64 / bbbv as f64,
+        }
+    }
+}
+
+impl Minesweeper {
+    // the measures of the game so far, which are normally looked at once it is finished
+    pub fn metrics(&self) -> Metrics {
+        // every click made is in the replay events, including ones that were undone
+        let mut clicks = Clicks::default();
+        for event in self.events.iter() {
+            match event.action {
+                Action::Open(_) => clicks.left += 1,
+                Action::Flag(_) => clicks.right += 1,
+                Action::Chord(_) => clicks.chord += 1,
+                Action::Undo | Action::Redo => {}
+            }
+        }
+        Metrics {
+            bbbv: self.bbbv(),
+            bbbv_solved: count_bbbv(self).1,
+            clicks,
+            time_ms: self.stopwatch.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+// the 3BV of the board, and how much of it has been opened:
+// one for each opening (an area of tiles with no mines around them), and one for each number not next to an opening
+// (an opening is solved once any of it is open, as opening one of its tiles opens the rest)
+pub(crate) fn count_bbbv(game: &Minesweeper) -> (u32, u32) {
+    let index = |coord| solver::index(game.dimensions, coord);
+    let mut counted = vec![false; game.tiles.len()];
+    let (mut bbbv, mut solved) = (0, 0);
+    for tile in game.tiles.iter() {
+        if tile.mines != TileMines::NoMine(0) || counted[index(tile.coord)] {
+            continue;
+        }
+        // mark the opening and the numbers around its edge so they aren't counted again
+        let mut open = false;
+        counted[index(tile.coord)] = true;
+        let mut stack = vec![tile.coord];
+        while let Some(coord) = stack.pop() {
+            open |= game.get_tile(coord).state == TileState::Open;
+            for neighbour in game.iter_neighbours(coord) {
+                if !counted[index(neighbour)] {
+                    counted[index(neighbour)] = true;
+                    if game.get_tile(neighbour).mines == TileMines::NoMine(0) {
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+        bbbv += 1;
+        solved += open as u32;
+    }
+    for tile in game.tiles.iter() {
+        if tile.mines != TileMines::Mine && !counted[index(tile.coord)] {
+            bbbv += 1;
+            solved += (tile.state == TileState::Open) as u32;
+        }
+    }
+    (bbbv, solved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::board;
+
+    #[test]
+    fn bbbv_of_hand_written_boards() {
+        // every safe tile is in one opening or on its edge
+        assert_eq!(count_bbbv(&board(&["*..", "...", "..."])), (1, 0));
+        // the 1 on the left isn't next to the opening, so needs its own click, and opening the 1 next to the
+        // opening doesn't solve the opening
+        assert_eq!(count_bbbv(&board(&["o*o..*"])), (2, 1));
+        // with no openings, every number is a click
+        assert_eq!(count_bbbv(&board(&[".*.", "...", ".*."])), (7, 0));
+    }
+}
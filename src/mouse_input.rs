@@ -0,0 +1,141 @@
+// which mouse buttons are held down on the board, so that tiles are opened when a button is released (like the
+// original game) and chording can be done with the middle button or by pressing the left and right buttons together
+
+use minesweeper::game::*;
+use piston_window::MouseButton;
+
+// a move made on the tile under the mouse
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BoardAction {
+    Open,
+    Flag,
+    // only opens around an open number, so a chord that misses doesn't open a tile
+    Chord,
+}
+impl BoardAction {
+    pub fn apply(self, game: &mut Minesweeper, coord: Coord) {
+        match self {
+            BoardAction::Open => game.open(coord),
+            BoardAction::Flag => game.flag(coord),
+            BoardAction::Chord => {
+                if game.get_tile(coord).state == TileState::Open {
+                    game.open(coord);
+                }
+            }
+        }
+        game.check_state();
+    }
+}
+
+#[derive(Default)]
+pub struct MouseInput {
+    left: bool,
+    right: bool,
+    middle: bool,
+    // the left and right buttons have both been held since they were both last released
+    chording: bool,
+    // the chord has been made by releasing one of the buttons, so releasing the other does nothing
+    chorded: bool,
+    // right clicking a number flags around it when released, so it doesn't happen if the left button is pressed to chord
+    flag_on_release: bool,
+}
+
+impl MouseInput {
+    // a button was pressed on a tile, which only flags straight away, like the original game
+    pub fn press(&mut self, button: MouseButton, tile_state: TileState) -> Option<BoardAction> {
+        match button {
+            MouseButton::Left => {
+                self.left = true;
+                self.chording |= self.right;
+                None
+            }
+            MouseButton::Right => {
+                self.right = true;
+                self.chording |= self.left;
+                if self.left {
+                    None
+                } else if tile_state == TileState::Open {
+                    self.flag_on_release = true;
+                    None
+                } else {
+                    Some(BoardAction::Flag)
+                }
+            }
+            MouseButton::Middle => {
+                self.middle = true;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    // a button was released anywhere, which only does something if it was pressed on the board
+    pub fn release(&mut self, button: MouseButton) -> Option<BoardAction> {
+        let chord = self.chording && !self.chorded;
+        let action = match button {
+            MouseButton::Left if self.left => {
+                self.left = false;
+                if chord {
+                    Some(BoardAction::Chord)
+                } else if !self.chording {
+                    Some(BoardAction::Open)
+                } else {
+                    None
+                }
+            }
+            MouseButton::Right if self.right => {
+                self.right = false;
+                if chord {
+                    Some(BoardAction::Chord)
+                } else if self.flag_on_release && !self.chording {
+                    Some(BoardAction::Flag)
+                } else {
+                    None
+                }
+            }
+            MouseButton::Middle if self.middle => {
+                self.middle = false;
+                Some(BoardAction::Chord)
+            }
+            _ => None,
+        };
+        self.chorded |= action == Some(BoardAction::Chord) && self.chording;
+        if !self.left && !self.right {
+            self.chording = false;
+            self.chorded = false;
+            self.flag_on_release = false;
+        }
+        action
+    }
+
+    // forgets the held buttons, e.g. when a dialog opens over the board
+    pub fn cancel(&mut self) {
+        *self = MouseInput::default();
+    }
+
+    // whether a button which opens tiles is held, as flagging with the right button alone doesn't make the face change
+    pub fn held(&self) -> bool {
+        self.left || self.middle
+    }
+
+    // the closed tiles which are shown pressed in while the buttons are held over them:
+    // the tile under the mouse when opening, and the tiles around it too when chording
+    pub fn pressed_tiles(&self, game: &Minesweeper, coord: Option<Coord>) -> Vec<Coord> {
+        let Some(coord) = coord else {
+            return Vec::new();
+        };
+        let mut tiles = vec![coord];
+        if (self.chording && !self.chorded) || self.middle {
+            tiles.extend(game.iter_neighbours(coord));
+        } else if !self.left || self.chording {
+            return Vec::new();
+        }
+        tiles.retain(|&tile| {
+            matches!(
+                game.get_tile(tile).state,
+                TileState::Closed | TileState::Questioned
+            )
+        });
+        tiles
+    }
+}
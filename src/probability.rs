@@ -0,0 +1,354 @@
+// works out the exact chance of each closed tile being a mine, using only what the player can see
+// the unknown tiles next to open tiles are split into groups which don't affect each other, every possible
+// arrangement of mines in each group is counted, and then the groups are combined with the number of ways
+// the rest of the mines could be arranged in the tiles which aren't next to any open tiles
+
+use crate::game::*;
+use crate::solver::{self, Knowledge};
+
+// counting arrangements takes exponentially longer the bigger a group is, so give up after this many steps
+const MAX_STEPS: u64 = 5_000_000;
+
+pub struct Probabilities {
+    dimensions: Coord,
+    // the chance of each tile being a mine, or None for open tiles
+    tiles: Vec<Option<f64>>,
+}
+impl Probabilities {
+    pub fn get(&self, coord: Coord) -> Option<f64> {
+        self.tiles[solver::index(self.dimensions, coord)]
+    }
+}
+
+// returns None if there are too many possible arrangements of mines to count them all in a reasonable time
+// like the solver, the number of mines left is worked out from the total mines rather than the flags
+pub fn mine_probabilities(game: &Minesweeper) -> Option<Probabilities> {
+    let dimensions = game.dimensions;
+    // tiles which can be solved logically don't need counting
+    let (_, knowledge) = solver::solve_with_knowledge(game);
+
+    let mut tiles: Vec<Option<f64>> = game
+        .tiles
+        .iter()
+        .zip(knowledge.iter())
+        .map(|(tile, knowledge)| match knowledge {
+            _ if tile.state == TileState::Open => None,
+            Knowledge::Mine => Some(1.),
+            Knowledge::Safe => Some(0.),
+            _ => None,
+        })
+        .collect();
+
+    // the open tiles which still have unknown tiles around them, with how many of those are mines
+    let mut constraints: Vec<(Vec<usize>, u8)> = Vec::new();
+    // which tiles are next to an open tile, and so are in one of the groups
+    let mut in_group = vec![false; knowledge.len()];
+    // a union-find of the tiles next to open tiles, so tiles that share an open tile end up in the same group
+    let mut parents: Vec<usize> = (0..knowledge.len()).collect();
+    for tile in game.tiles.iter() {
+        let Knowledge::Open(n) = knowledge[solver::index(dimensions, tile.coord)] else {
+            continue;
+        };
+        let mut unknown = Vec::new();
+        let mut mines = 0;
+        for neighbour in game.iter_neighbours(tile.coord) {
+            let i = solver::index(dimensions, neighbour);
+            match knowledge[i] {
+                Knowledge::Unknown => unknown.push(i),
+                Knowledge::Mine => mines += 1,
+                _ => {}
+            }
+        }
+        if unknown.is_empty() {
+            continue;
+        }
+        for &i in unknown.iter() {
+            in_group[i] = true;
+            union(&mut parents, unknown[0], i);
+        }
+        constraints.push((unknown, n.saturating_sub(mines)));
+    }
+
+    // split the tiles and constraints into their groups
+    let mut groups: Vec<Group> = Vec::new();
+    let mut group_index = vec![usize::MAX; knowledge.len()];
+    // the position of each tile in its group
+    let mut group_of = vec![None; knowledge.len()];
+    for (i, _) in in_group
+        .iter()
+        .enumerate()
+        .filter(|(_, &in_group)| in_group)
+    {
+        let root = find(&mut parents, i);
+        if group_index[root] == usize::MAX {
+            group_index[root] = groups.len();
+            groups.push(Group::default());
+        }
+        let group = &mut groups[group_index[root]];
+        group_of[i] = Some(group.tiles.len());
+        group.tiles.push(i);
+    }
+    for (tiles, mines) in constraints {
+        let group = &mut groups[group_index[find(&mut parents, tiles[0])]];
+        let tiles = tiles.iter().map(|&i| group_of[i].unwrap()).collect();
+        group.constraints.push((tiles, mines));
+    }
+
+    let mut steps = 0;
+    let mut arrangements = Vec::new();
+    for group in groups.iter() {
+        arrangements.push(count_arrangements(group, &mut steps)?);
+    }
+
+    // the tiles which aren't next to any open tiles, and the mines which haven't been found
+    let other_tiles = knowledge
+        .iter()
+        .zip(group_of.iter())
+        .filter(|(&k, group)| k == Knowledge::Unknown && group.is_none())
+        .count();
+    let known_mines = knowledge.iter().filter(|&&k| k == Knowledge::Mine).count();
+    let mines_left = (game.mines() as usize).saturating_sub(known_mines);
+
+    // the relative number of ways the mines which aren't in any group can be arranged in the other tiles,
+    // for each number of mines in the groups (this is a binomial coefficient, which is calculated
+    // relative to the others as logarithms, as the real numbers are far too big for a float)
+    let max_group_mines: usize = arrangements.iter().map(|a| a.len() - 1).sum();
+    let mut log_ways = vec![f64::NEG_INFINITY; max_group_mines + 1];
+    let mut log_choose = 0.;
+    for m in 0..=mines_left.min(other_tiles) {
+        if m > 0 {
+            log_choose += ((other_tiles - m + 1) as f64).ln() - (m as f64).ln();
+        }
+        if mines_left - m <= max_group_mines {
+            log_ways[mines_left - m] = log_choose;
+        }
+    }
+    let max_log_ways = log_ways.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max_log_ways == f64::NEG_INFINITY {
+        // there is no way to fit the mines on the board, so what the player can see doesn't make sense
+        return None;
+    }
+    let ways: Vec<f64> = log_ways.iter().map(|l| (l - max_log_ways).exp()).collect();
+
+    // the number of arrangements of the groups before and after each group, for each number of mines
+    let mut before = vec![vec![1.]];
+    for arrangement in arrangements.iter() {
+        before.push(convolve(before.last().unwrap(), &counts(arrangement)));
+    }
+    let mut after = vec![vec![1.]; arrangements.len() + 1];
+    for i in (0..arrangements.len()).rev() {
+        after[i] = convolve(&after[i + 1], &counts(&arrangements[i]));
+    }
+
+    for (i, (group, arrangement)) in groups.iter().zip(arrangements.iter()).enumerate() {
+        // the weight of each number of mines in this group, from all the ways the rest of the board can be arranged
+        let others = convolve(&before[i], &after[i + 1]);
+        let weights: Vec<f64> = (0..arrangement.len())
+            .map(|k| {
+                others
+                    .iter()
+                    .enumerate()
+                    .map(|(other_mines, count)| count * ways[k + other_mines])
+                    .sum()
+            })
+            .collect();
+        let total: f64 = (0..arrangement.len())
+            .map(|k| arrangement[k].0 * weights[k])
+            .sum();
+        if total == 0. {
+            return None;
+        }
+        for (j, &tile) in group.tiles.iter().enumerate() {
+            let with_mine: f64 = (0..arrangement.len())
+                .map(|k| arrangement[k].1[j] * weights[k])
+                .sum();
+            tiles[tile] = Some(with_mine / total);
+        }
+    }
+
+    // every tile away from the open tiles has the same chance of being a mine
+    if other_tiles > 0 {
+        let all_groups = &before[arrangements.len()];
+        let mut total = 0.;
+        let mut mines = 0.;
+        for (group_mines, count) in all_groups.iter().enumerate() {
+            total += count * ways[group_mines];
+            mines += count * ways[group_mines] * (mines_left.saturating_sub(group_mines)) as f64;
+        }
+        let chance = mines / total / other_tiles as f64;
+        for (i, knowledge) in knowledge.iter().enumerate() {
+            if *knowledge == Knowledge::Unknown && group_of[i].is_none() {
+                tiles[i] = Some(chance);
+            }
+        }
+    }
+
+    Some(Probabilities { dimensions, tiles })
+}
+
+// a group of unknown tiles (as indexes into the board) and the constraints between them
+// (as indexes into the group's tiles, and the number of mines in those tiles)
+#[derive(Default)]
+struct Group {
+    tiles: Vec<usize>,
+    constraints: Vec<(Vec<usize>, u8)>,
+}
+
+// for each possible number of mines in the group, returns the number of arrangements with that many mines,
+// and how many of those arrangements have a mine in each tile
+// (these are scaled down by the same amount when they get too big, which doesn't change the probabilities)
+fn count_arrangements(group: &Group, steps: &mut u64) -> Option<Vec<(f64, Vec<f64>)>> {
+    let mut tile_constraints = vec![Vec::new(); group.tiles.len()];
+    for (c, (tiles, _)) in group.constraints.iter().enumerate() {
+        for &tile in tiles {
+            tile_constraints[tile].push(c);
+        }
+    }
+    let mut counter = Counter {
+        tile_constraints,
+        required: group.constraints.iter().map(|(_, mines)| *mines).collect(),
+        placed: vec![0; group.constraints.len()],
+        unplaced: group
+            .constraints
+            .iter()
+            .map(|(tiles, _)| tiles.len() as u8)
+            .collect(),
+        mines: vec![false; group.tiles.len()],
+        results: vec![(0., vec![0.; group.tiles.len()]); group.tiles.len() + 1],
+        steps,
+    };
+    if !counter.place(0, 0) {
+        return None;
+    }
+    // remove the impossible numbers of mines from the end
+    while counter.results.len() > 1 && counter.results.last().unwrap().0 == 0. {
+        counter.results.pop();
+    }
+    Some(counter.results)
+}
+
+struct Counter<'a> {
+    // the constraints each tile is part of
+    tile_constraints: Vec<Vec<usize>>,
+    // for each constraint, the mines it needs and the mines and tiles without a decision so far
+    required: Vec<u8>,
+    placed: Vec<u8>,
+    unplaced: Vec<u8>,
+    // whether each tile has a mine in the arrangement being built
+    mines: Vec<bool>,
+    results: Vec<(f64, Vec<f64>)>,
+    steps: &'a mut u64,
+}
+
+impl Counter<'_> {
+    // tries both a mine and no mine in the tile, then moves on to the next tile
+    // returns false if it has taken too many steps
+    fn place(&mut self, tile: usize, mines: usize) -> bool {
+        *self.steps += 1;
+        if *self.steps > MAX_STEPS {
+            return false;
+        }
+        if tile == self.mines.len() {
+            let result = &mut self.results[mines];
+            result.0 += 1.;
+            for (count, &mine) in result.1.iter_mut().zip(self.mines.iter()) {
+                if mine {
+                    *count += 1.;
+                }
+            }
+            return true;
+        }
+
+        for mine in [false, true] {
+            let mut possible = true;
+            for &c in self.tile_constraints[tile].iter() {
+                self.unplaced[c] -= 1;
+                self.placed[c] += mine as u8;
+                if self.placed[c] > self.required[c]
+                    || self.placed[c] + self.unplaced[c] < self.required[c]
+                {
+                    possible = false;
+                }
+            }
+            self.mines[tile] = mine;
+            let finished = !possible || self.place(tile + 1, mines + mine as usize);
+            for &c in self.tile_constraints[tile].iter() {
+                self.unplaced[c] += 1;
+                self.placed[c] -= mine as u8;
+            }
+            if !finished {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn counts(arrangements: &[(f64, Vec<f64>)]) -> Vec<f64> {
+    arrangements.iter().map(|(count, _)| *count).collect()
+}
+
+// combines the number of arrangements of two sets of groups by the total number of mines in them,
+// scaling the result so it can't get too big for a float
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+    let max = result.iter().copied().fold(0., f64::max);
+    if max > 0. {
+        result.iter_mut().for_each(|x| *x /= max);
+    }
+    result
+}
+
+fn find(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    // point everything on the way straight at the root so later finds are quicker
+    let mut i = i;
+    while parents[i] != root {
+        let next = parents[i];
+        parents[i] = root;
+        i = next;
+    }
+    root
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parents, a), find(parents, b));
+    parents[a] = b;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::{arrangements, random_board};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn matches_counting_every_arrangement() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..300 {
+            let game = random_board(&mut rng);
+            let arrangements = arrangements(&game);
+            let probabilities = mine_probabilities(&game).unwrap();
+            for tile in game.tiles.iter() {
+                let chance = probabilities.get(tile.coord);
+                if tile.state == TileState::Open {
+                    assert_eq!(chance, None);
+                    continue;
+                }
+                let i = solver::index(game.dimensions, tile.coord);
+                let with_mine = arrangements.iter().filter(|mines| mines[i]).count();
+                let expected = with_mine as f64 / arrangements.len() as f64;
+                assert!((chance.unwrap() - expected).abs() < 1e-9);
+            }
+        }
+    }
+}
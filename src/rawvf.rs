@@ -0,0 +1,373 @@
+// replays in the RAW-VF text format used by the minesweeper community, so games can be shared with other programs
+// a file is a list of "Name: value" headers, then the board after "Board:" with * for a mine and 0 for a safe tile,
+// then the mouse events after "Events:" as the time in seconds, the event and the tile (counting from 1):
+//
+//     RawVF_Version: Rev5
+//     Width: 9
+//     ...
+//     Board:
+//     *00000000
+//     ...
+//     Events:
+//     0.000 lc 5 5 (72 72)
+//     0.000 lr 5 5 (72 72)
+//
+// the events are left, right and middle button presses (lc, rc, mc) and releases (lr, rr, mr), and mouse moves (mv)
+// (only the features this game has are supported, so games played in other modes, like without flags, can't be imported)
+
+use crate::files::{self, FileError};
+use crate::game::*;
+use crate::metrics::Metrics;
+use crate::replay::{self, Action, Replay, ReplayEvent};
+use crate::solver;
+use std::{fs, path::Path};
+
+// the size of a tile in pixels for the mouse positions written with each event, which is the usual size in other programs
+const PIXELS_PER_TILE: u32 = 16;
+
+impl Replay {
+    pub fn export_rawvf(&self, path: &Path) -> Result<(), FileError> {
+        files::write_text(path, &self.to_rawvf()?)
+    }
+
+    pub fn import_rawvf(path: &Path) -> Result<Self, FileError> {
+        Self::from_rawvf(&fs::read_to_string(path)?)
+    }
+
+    // the replay as RAW-VF, which can't have undo or redo in it
+    pub fn to_rawvf(&self) -> Result<String, FileError> {
+        let mut game = self.new_game();
+        let (dimensions, _) = self.difficulty.board_info();
+        let level = match (dimensions.x, dimensions.y, game.mines()) {
+            (9, 9, 10) => "Beginner",
+            (16, 16, 40) => "Intermediate",
+            (30, 16, 99) => "Expert",
+            _ => "Custom",
+        };
+        let mut text = format!(
+            "RawVF_Version: Rev5\nProgram: Minesweeper\nVersion: {}\nLevel: {level}\nWidth: {}\nHeight: {}\nMines: {}\nMarks: {}\nTime: {:.3}\nBBBV: {}\nBoard:\n",
+            env!("CARGO_PKG_VERSION"),
+            dimensions.x,
+            dimensions.y,
+            game.mines(),
+            if self.question_marks { "On" } else { "Off" },
+            self.duration_ms() as f64 / 1000.,
+            game.bbbv(),
+        );
+        for row in game.tiles.chunks(dimensions.x as usize) {
+            for tile in row {
+                text.push(match tile.mines {
+                    TileMines::Mine => '*',
+                    TileMines::NoMine(_) => '0',
+                });
+            }
+            text.push('\n');
+        }
+
+        text.push_str("Events:\n");
+        for event in self.events.iter() {
+            // the moves are made on a copy of the game, as what a click does depends on the tiles around it
+            let states_before: Vec<TileState> = game.tiles.iter().map(|tile| tile.state).collect();
+            let state_before = |coord| states_before[solver::index(dimensions, coord)];
+            event.action.apply(&mut game);
+            let clicks: Vec<(char, Coord)> = match event.action {
+                Action::Open(coord) | Action::Chord(coord) => match state_before(coord) {
+                    TileState::Closed | TileState::Questioned => vec![('l', coord)],
+                    // opening a flagged tile unflags it, which other programs do with right clicks
+                    TileState::Flagged => {
+                        let clicks = right_clicks(
+                            TileState::Flagged,
+                            TileState::Closed,
+                            self.question_marks,
+                        );
+                        vec![('r', coord); clicks]
+                    }
+                    TileState::Open => vec![('m', coord)],
+                },
+                // flagging a number flags or unflags the tiles around it, which other programs do one at a time
+                Action::Flag(coord) if state_before(coord) == TileState::Open => game
+                    .iter_neighbours(coord)
+                    .flat_map(|neighbour| {
+                        let clicks = right_clicks(
+                            state_before(neighbour),
+                            game.get_tile(neighbour).state,
+                            self.question_marks,
+                        );
+                        std::iter::repeat_n(('r', neighbour), clicks)
+                    })
+                    .collect(),
+                Action::Flag(coord) => vec![('r', coord)],
+                Action::Undo | Action::Redo => {
+                    return Err(FileError::Unsupported(
+                        "exporting a game with undone moves to RAW-VF".to_string(),
+                    ))
+                }
+            };
+            for (button, coord) in clicks {
+                // a press and a release of the button on the middle of the tile
+                for kind in ['c', 'r'] {
+                    text.push_str(&format!(
+                        "{:.3} {button}{kind} {} {} ({} {})\n",
+                        event.time_ms as f64 / 1000.,
+                        coord.x + 1,
+                        coord.y + 1,
+                        coord.x as u32 * PIXELS_PER_TILE + PIXELS_PER_TILE / 2,
+                        coord.y as u32 * PIXELS_PER_TILE + PIXELS_PER_TILE / 2,
+                    ));
+                }
+            }
+        }
+        Ok(text)
+    }
+
+    pub fn from_rawvf(text: &str) -> Result<Self, FileError> {
+        let parse_error = |line: usize, message: String| FileError::Parse { line, message };
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()));
+
+        // the headers, up to the board
+        let mut width = None;
+        let mut height = None;
+        let mut question_marks = false;
+        for (line_number, line) in lines.by_ref() {
+            if line == "Board:" {
+                break;
+            }
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (name, value) = (name.trim(), value.trim());
+            match name {
+                "Width" => width = value.parse::<u16>().ok(),
+                "Height" => height = value.parse::<u16>().ok(),
+                "Marks" => question_marks = value.eq_ignore_ascii_case("on"),
+                "Mode" if !["classic", "normal"].contains(&value.to_lowercase().as_str()) => {
+                    return Err(FileError::Unsupported(format!("the {value} mode")))
+                }
+                _ => {}
+            }
+            if (name == "Width" && width.is_none()) || (name == "Height" && height.is_none()) {
+                return Err(parse_error(
+                    line_number,
+                    format!("\"{value}\" isn't a valid size"),
+                ));
+            }
+        }
+        let (Some(width), Some(height)) = (width, height) else {
+            return Err(parse_error(
+                1,
+                "the Width and Height headers and the Board are needed".to_string(),
+            ));
+        };
+
+        // the board, up to the events
+        let mut mines = Vec::new();
+        let mut rows: u16 = 0;
+        let mut last_line = 0;
+        for (line_number, line) in lines.by_ref() {
+            last_line = line_number;
+            if line == "Events:" {
+                break;
+            }
+            if rows == height || line.chars().count() != width as usize {
+                return Err(parse_error(
+                    line_number,
+                    format!("the board should be {width}x{height}"),
+                ));
+            }
+            let row_mines = files::board_row_mines(line, rows, &['0', '.']).map_err(|c| {
+                parse_error(line_number, format!("'{c}' isn't a tile on the board"))
+            })?;
+            mines.extend(row_mines);
+            rows += 1;
+        }
+        if rows != height {
+            return Err(parse_error(
+                last_line,
+                format!("expected {height} rows on the board, but found {rows}"),
+            ));
+        }
+        // (the mines are already placed, so the board doesn't need to leave room for a safe first click)
+        let difficulty = Difficulty::custom_layout(width, height, mines.len() as u32)
+            .map_err(|e| parse_error(last_line, e.to_string()))?;
+        let difficulty = files::standard_difficulty(difficulty);
+
+        // the events, which are turned into moves by playing them on the board
+        let mut replay = Replay {
+            version: replay::VERSION,
+            difficulty,
+            seed: 0,
+            no_guess: false,
+            question_marks,
+            mines,
+            events: Vec::new(),
+            hints_used: 0,
+            metrics: Metrics::default(),
+        };
+        let mut game = replay.new_game();
+        let mut buttons = Buttons::default();
+        for (line_number, line) in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let time_ms = parts[0]
+                .parse::<f64>()
+                .map_err(|_| parse_error(line_number, format!("\"{}\" isn't a time", parts[0])))?;
+            // clicks before the game started can have negative times
+            let time_ms = (time_ms.max(0.) * 1000.).round() as u64;
+            let kind = parts.get(1).copied().unwrap_or_default();
+            // the changes of the game's state, which are worked out again from the moves
+            if ["start", "won", "lost", "blast", "boom"].contains(&kind) {
+                continue;
+            }
+            if !["lc", "rc", "mc", "lr", "rr", "mr", "mv"].contains(&kind) {
+                return Err(FileError::Unsupported(format!(
+                    "the \"{kind}\" event on line {line_number}"
+                )));
+            }
+            // clicks after the game has ended don't do anything, but are still checked above
+            if game.state != GameState::Playing {
+                continue;
+            }
+            let (x, y) = parts
+                .get(2)
+                .zip(parts.get(3))
+                .and_then(|(x, y)| Some((x.parse::<u16>().ok()?, y.parse::<u16>().ok()?)))
+                .ok_or_else(|| {
+                    parse_error(line_number, "expected the tile after the event".to_string())
+                })?;
+            // clicks off the board are kept as None, as they still change which buttons are held
+            let coord = (x >= 1 && y >= 1 && x <= width && y <= height)
+                .then(|| Coord { x: x - 1, y: y - 1 });
+            let action = match kind {
+                "mv" => None,
+                _ => buttons.event(kind, coord.map(|coord| (coord, game.get_tile(coord).state))),
+            };
+            if let Some(action) = action {
+                action.apply(&mut game);
+                replay.events.push(ReplayEvent { time_ms, action });
+            }
+        }
+        replay.metrics = Metrics {
+            time_ms: replay.duration_ms(),
+            ..game.metrics()
+        };
+        Ok(replay)
+    }
+}
+
+// how many right clicks take a tile from one state to another, as they go round from closed to flagged
+// (to a question mark if they are turned on) and back to closed
+fn right_clicks(from: TileState, to: TileState, question_marks: bool) -> usize {
+    let cycle: &[TileState] = if question_marks {
+        &[TileState::Closed, TileState::Flagged, TileState::Questioned]
+    } else {
+        &[TileState::Closed, TileState::Flagged]
+    };
+    let position = |state| cycle.iter().position(|&s| s == state).unwrap_or(0);
+    (position(to) + cycle.len() - position(from)) % cycle.len()
+}
+
+// which mouse buttons are held while reading the events, as a chord is made by pressing left and right together
+#[derive(Default)]
+struct Buttons {
+    left: bool,
+    right: bool,
+    // both buttons have been held since they were last both released
+    chording: bool,
+}
+impl Buttons {
+    // the move made by a mouse event on a tile (with the tile's state), if any
+    fn event(&mut self, kind: &str, tile: Option<(Coord, TileState)>) -> Option<Action> {
+        let action = match kind {
+            "lc" => {
+                self.left = true;
+                self.chording |= self.right;
+                None
+            }
+            "rc" => {
+                self.right = true;
+                self.chording |= self.left;
+                // right clicks flag, mark or unmark on press, but do nothing on a number
+                match tile {
+                    Some((coord, state)) if state != TileState::Open && !self.left => {
+                        Some(Action::Flag(coord))
+                    }
+                    _ => None,
+                }
+            }
+            // the first button released after a chord makes it, and the other does nothing
+            "lr" | "rr" => {
+                let chord = self.chording && self.left && self.right;
+                let open = kind == "lr" && !self.chording;
+                if kind == "lr" {
+                    self.left = false;
+                } else {
+                    self.right = false;
+                }
+                match tile {
+                    Some((coord, TileState::Open)) if chord => Some(Action::Chord(coord)),
+                    Some((coord, TileState::Closed | TileState::Questioned)) if open => {
+                        Some(Action::Open(coord))
+                    }
+                    Some((coord, TileState::Open)) if open => Some(Action::Chord(coord)),
+                    _ => None,
+                }
+            }
+            "mr" => match tile {
+                Some((coord, TileState::Open)) => Some(Action::Chord(coord)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if !self.left && !self.right {
+            self.chording = false;
+        }
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exporting_and_importing_keeps_the_game() {
+        let mut game = Minesweeper::with_seed(Difficulty::Medium, false, 7);
+        game.question_marks = true;
+        game.open(Coord { x: 8, y: 8 });
+        let closed: Vec<Coord> = game
+            .tiles
+            .iter()
+            .filter(|tile| tile.state == TileState::Closed)
+            .map(|tile| tile.coord)
+            .take(3)
+            .collect();
+        // a flag taken away by opening the tile, a question mark, and a flag
+        game.flag(closed[0]);
+        game.open(closed[0]);
+        game.flag(closed[1]);
+        game.flag(closed[1]);
+        game.flag(closed[2]);
+
+        let text = game.replay().to_rawvf().unwrap();
+        let imported = Replay::from_rawvf(&text).unwrap();
+        assert!(imported.question_marks);
+        let mut played = imported.new_game();
+        for event in imported.events.iter() {
+            event.action.apply(&mut played);
+        }
+
+        let states =
+            |game: &Minesweeper| game.tiles.iter().map(|tile| tile.state).collect::<Vec<_>>();
+        assert_eq!(states(&played), states(&game));
+        assert_eq!(played.get_tile(closed[0]).state, TileState::Closed);
+        assert_eq!(played.get_tile(closed[1]).state, TileState::Questioned);
+        assert_eq!(played.get_tile(closed[2]).state, TileState::Flagged);
+        assert_eq!(played.mines_left, game.mines_left);
+        assert_eq!(played.bbbv(), game.bbbv());
+    }
+}
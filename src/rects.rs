@@ -1,8 +1,7 @@
 // functions which return rectangles to be either drawn or used in the application logic
 
+use crate::board_view::*;
 use crate::consts::*;
-use crate::game::*;
-use piston_window::*;
 
 pub fn mouse_intersects_rect(mouse_pos: [f64; 2], rect: [f64; 4]) -> bool {
     mouse_pos[0] >= rect[0]
@@ -11,75 +10,150 @@ pub fn mouse_intersects_rect(mouse_pos: [f64; 2], rect: [f64; 4]) -> bool {
         && mouse_pos[1] <= rect[1] + rect[3]
 }
 
-pub fn get_window_size(game: &Minesweeper) -> [f64; 2] {
+// the size of the window at a scale of 1, which is the size everything else is laid out in
+pub fn get_window_size(board_view: &BoardView) -> [f64; 2] {
     [
-        game.dimensions.x as f64 * TILE_SIZE + BORDER_SIZE * 2.,
-        game.dimensions.y as f64 * TILE_SIZE
+        board_view.rect[2] + BORDER_SIZE * 2.,
+        board_view.rect[3]
             + BORDER_MIDDLE_POS
             + BORDER_SIZE
             + BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
     ]
 }
 
-pub fn get_face_button_rect(window: &PistonWindow) -> [f64; 4] {
+// where the layout is drawn in the window, scaled to fit it without stretching and centred in the space left over
+pub fn get_view_rect(window_size: [f64; 2], layout_size: [f64; 2]) -> [f64; 4] {
+    let scale = f64::min(
+        window_size[0] / layout_size[0],
+        window_size[1] / layout_size[1],
+    );
     [
-        (window.size().width / 2.) - (FACE_BUTTON_SIZE / 2.),
+        (window_size[0] - layout_size[0] * scale) / 2.,
+        (window_size[1] - layout_size[1] * scale) / 2.,
+        layout_size[0] * scale,
+        layout_size[1] * scale,
+    ]
+}
+
+pub fn get_face_button_rect(window_size: [f64; 2]) -> [f64; 4] {
+    [
+        (window_size[0] / 2.) - (FACE_BUTTON_SIZE / 2.),
         (BORDER_MIDDLE_POS + BORDER_SIZE) / 2. - (FACE_BUTTON_SIZE / 2.),
         FACE_BUTTON_SIZE,
         FACE_BUTTON_SIZE,
     ]
 }
 
-pub fn get_difficulty_buttons_rects(window: &PistonWindow) -> [[f64; 4]; 3] {
-    let easy_button_width = DIFFICULTY_BUTTON_HEIGHT * 2.27;
-    let medium_button_width = DIFFICULTY_BUTTON_HEIGHT * 3.;
-    let hard_button_width = DIFFICULTY_BUTTON_HEIGHT * 2.27;
-
-    let button_y = window.size().height
-        - ((BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR) / 2.)
-        - (DIFFICULTY_BUTTON_HEIGHT / 2.);
+// the hint button is in the middle of the space between the face button and the stopwatch
+pub fn get_hint_button_rect(window_size: [f64; 2]) -> [f64; 4] {
+    let face_button_rect = get_face_button_rect(window_size);
+    let face_button_right = face_button_rect[0] + face_button_rect[2];
+    // (where the stopwatch starts when it has three digits, as it can be wider but doesn't move the button)
+    let stopwatch_left = window_size[0] - BORDER_SIZE - 3. - NUM_DISPLAY_WIDTH;
     [
-        // easy button
-        [
-            (window.size().width / 2.)
-                - (medium_button_width / 2.)
-                - DIFFICULTY_BUTTON_SPACING
-                - easy_button_width,
-            button_y,
-            easy_button_width,
-            DIFFICULTY_BUTTON_HEIGHT,
-        ],
-        // medium button
-        [
-            (window.size().width / 2.) - (medium_button_width / 2.),
-            button_y,
-            medium_button_width,
-            DIFFICULTY_BUTTON_HEIGHT,
-        ],
-        // hard button
-        [
-            (window.size().width / 2.) + (medium_button_width / 2.) + DIFFICULTY_BUTTON_SPACING,
+        (face_button_right + stopwatch_left) / 2. - (HINT_BUTTON_SIZE / 2.),
+        (BORDER_MIDDLE_POS + BORDER_SIZE) / 2. - (HINT_BUTTON_SIZE / 2.),
+        HINT_BUTTON_SIZE,
+        HINT_BUTTON_SIZE,
+    ]
+}
+
+pub fn get_difficulty_buttons_rects(window_size: [f64; 2]) -> [[f64; 4]; 4] {
+    // the width of each button (easy, medium, hard, custom) as a multiple of its height
+    let width_ratios = [2.27, 3., 2.27, 3.];
+    let spacing_ratio = DIFFICULTY_BUTTON_SPACING / DIFFICULTY_BUTTON_HEIGHT;
+
+    // shrink the buttons if they don't all fit in the bottom border (e.g. on narrow custom boards)
+    let total_ratio =
+        width_ratios.iter().sum::<f64>() + spacing_ratio * (width_ratios.len() - 1) as f64;
+    let available_width = window_size[0] - BORDER_SIZE * 2.;
+    let button_height = f64::min(DIFFICULTY_BUTTON_HEIGHT, available_width / total_ratio);
+    let spacing = button_height * spacing_ratio;
+
+    let button_y =
+        window_size[1] - ((BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR) / 2.) - (button_height / 2.);
+
+    let mut button_x = (window_size[0] / 2.) - (button_height * total_ratio / 2.);
+    let mut rects = [[0.; 4]; 4];
+    for (rect, width_ratio) in rects.iter_mut().zip(width_ratios) {
+        *rect = [
+            button_x,
             button_y,
-            hard_button_width,
-            DIFFICULTY_BUTTON_HEIGHT,
-        ],
+            button_height * width_ratio,
+            button_height,
+        ];
+        button_x += button_height * width_ratio + spacing;
+    }
+    rects
+}
+
+// the progress bar of a replay is drawn along the border between the top of the window and the board
+pub fn get_replay_bar_rect(window_size: [f64; 2]) -> [f64; 4] {
+    [
+        BORDER_SIZE,
+        BORDER_MIDDLE_POS + BORDER_SIZE * 0.3,
+        window_size[0] - BORDER_SIZE * 2.,
+        BORDER_SIZE * 0.4,
     ]
 }
 
-pub fn get_mines_left_rect() -> [f64; 4] {
+// dialogs are drawn in the middle of the area of the window that the board is drawn in
+pub fn get_dialog_rect(board_view: &BoardView) -> [f64; 4] {
+    let board_rect = board_view.rect;
+    let size = [DIALOG_WIDTH, DIALOG_HEIGHT];
     [
-        BORDER_SIZE + 3.,
-        (BORDER_MIDDLE_POS + BORDER_SIZE) / 2. - (NUM_DISPLAY_HEIGHT / 2.),
-        NUM_DISPLAY_WIDTH,
-        NUM_DISPLAY_HEIGHT,
+        board_rect[0] + (board_rect[2] - size[0]) / 2.,
+        board_rect[1] + (board_rect[3] - size[1]) / 2.,
+        size[0],
+        size[1],
     ]
 }
 
-pub fn get_stopwatch_rect(window: &PistonWindow) -> [f64; 4] {
+// the width of a number display for some text (digits, decimal points and minus signs), as a multiple of its height
+fn num_display_ratio(text: &str) -> f64 {
+    let glyphs: f64 = text
+        .chars()
+        .map(|c| match c {
+            '.' => NUM_DISPLAY_TEXTURE_POINT,
+            _ => NUM_DISPLAY_TEXTURE_DIGIT,
+        })
+        .sum();
+    let gaps = text.chars().count().saturating_sub(1) as f64 * NUM_DISPLAY_TEXTURE_GAP;
+    (NUM_DISPLAY_TEXTURE_EDGE * 2. + glyphs + gaps) / NUM_DISPLAY_TEXTURE_HEIGHT
+}
+
+// the size of a number display, which is made smaller if it would be wider than the space it has
+fn num_display_size(text: &str, max_width: f64) -> [f64; 2] {
+    let ratio = num_display_ratio(text);
+    let height = f64::min(NUM_DISPLAY_HEIGHT, max_width / ratio);
+    [height * ratio, height]
+}
+
+// the mines left display is on the left of the top border, and can reach up to the face button
+pub fn get_mines_left_rect(window_size: [f64; 2], text: &str) -> [f64; 4] {
+    let left = BORDER_SIZE + 3.;
+    let size = num_display_size(text, get_face_button_rect(window_size)[0] - left);
     [
-        window.size().width - BORDER_SIZE - 3. - NUM_DISPLAY_WIDTH,
-        (BORDER_MIDDLE_POS + BORDER_SIZE) / 2. - (NUM_DISPLAY_HEIGHT / 2.),
+        left,
+        (BORDER_MIDDLE_POS + BORDER_SIZE) / 2. - (size[1] / 2.),
+        size[0],
+        size[1],
+    ]
+}
+
+// the stopwatch is on the right of the top border, and can reach up to the hint button
+pub fn get_stopwatch_rect(window_size: [f64; 2], text: &str) -> [f64; 4] {
+    let right = window_size[0] - BORDER_SIZE - 3.;
+    let hint_button_rect = get_hint_button_rect(window_size);
+    let max_width = f64::max(
+        right - (hint_button_rect[0] + hint_button_rect[2]),
         NUM_DISPLAY_WIDTH,
-        NUM_DISPLAY_HEIGHT,
+    );
+    let size = num_display_size(text, max_width);
+    [
+        right - size[0],
+        (BORDER_MIDDLE_POS + BORDER_SIZE) / 2. - (size[1] / 2.),
+        size[0],
+        size[1],
     ]
 }
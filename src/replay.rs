@@ -0,0 +1,109 @@
+// recordings of games as the moves that were made and when, so they can be saved and watched back
+
+use crate::files::{self, FileError};
+use crate::game::*;
+use crate::metrics::Metrics;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// the version of the replay file format, which should be increased whenever it changes
+pub const VERSION: u32 = 1;
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    Open(Coord),
+    Flag(Coord),
+    // opening around an open number whose mines are all flagged
+    Chord(Coord),
+    Undo,
+    Redo,
+}
+impl Action {
+    // makes the move on the game, the same way it was made when it was recorded
+    pub fn apply(self, game: &mut Minesweeper) {
+        match self {
+            Action::Open(coord) | Action::Chord(coord) => game.open(coord),
+            Action::Flag(coord) => game.flag(coord),
+            Action::Undo => {
+                game.undo();
+            }
+            Action::Redo => {
+                game.redo();
+            }
+        }
+        game.check_state();
+    }
+
+    // the tile the move was made on, if it was made on one
+    pub fn coord(self) -> Option<Coord> {
+        match self {
+            Action::Open(coord) | Action::Flag(coord) | Action::Chord(coord) => Some(coord),
+            Action::Undo | Action::Redo => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    // the time on the stopwatch when the move was made (so the first tile opened is always at 0)
+    pub time_ms: u64,
+    pub action: Action,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub difficulty: Difficulty,
+    pub seed: u64,
+    pub no_guess: bool,
+    // whether flags were turned into question marks, which changes what flagging a flag does
+    pub question_marks: bool,
+    // the mines are stored rather than regenerated from the seed, so replays still work if generation changes
+    pub mines: Vec<Coord>,
+    pub events: Vec<ReplayEvent>,
+    // how many hints were given, so a hinted game can be told apart from one played without help
+    pub hints_used: u32,
+    // the measures of the game as it was when the replay was made
+    pub metrics: Metrics,
+}
+
+impl Replay {
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        files::write_json(path, self)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        let replay: Replay = files::read_json(path, VERSION)?;
+        // make sure the game can be set up without panicking
+        let dimensions = files::checked_dimensions(replay.difficulty)?;
+        let in_board = |coord: Coord| coord.x < dimensions.x && coord.y < dimensions.y;
+        let event_coords = replay
+            .events
+            .iter()
+            .filter_map(|event| event.action.coord());
+        if !replay
+            .mines
+            .iter()
+            .copied()
+            .chain(event_coords)
+            .all(in_board)
+        {
+            return Err(FileError::InvalidBoard);
+        }
+        Ok(replay)
+    }
+
+    // a new game with the mines in the same places as the recorded game, before any moves have been made
+    pub fn new_game(&self) -> Minesweeper {
+        let mut game = Minesweeper::with_layout(self.difficulty, &self.mines);
+        game.seed = self.seed;
+        game.no_guess = self.no_guess;
+        game.question_marks = self.question_marks;
+        game
+    }
+
+    // how long the replay lasts, up to the last move
+    pub fn duration_ms(&self) -> u64 {
+        self.events.last().map_or(0, |event| event.time_ms)
+    }
+}
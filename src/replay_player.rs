@@ -0,0 +1,194 @@
+// plays back a recorded game in the window, which can be paused, stepped through, seeked and sped up
+
+use minesweeper::game::*;
+use minesweeper::replay::Replay;
+use piston_window::*;
+use std::time::Duration;
+
+use crate::consts::*;
+
+const MIN_SPEED: f64 = 0.125;
+const MAX_SPEED: f64 = 16.;
+// how often the replay is drawn while it plays, so the progress bar and time move smoothly
+const FRAME_TIME: Duration = Duration::from_micros(16_667);
+
+pub struct ReplayPlayer {
+    replay: Replay,
+    // the game as it is at the current point in the replay
+    pub game: Minesweeper,
+    // how many of the replay's moves have been made on the game
+    applied: usize,
+    // how far through the replay is, which goes up in real time multiplied by the speed
+    time_ms: f64,
+    playing: bool,
+    speed: f64,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        ReplayPlayer {
+            game: replay.new_game(),
+            replay,
+            applied: 0,
+            time_ms: 0.,
+            playing: true,
+            speed: 1.,
+        }
+    }
+
+    // the time to show on the stopwatch
+    pub fn time(&self) -> Duration {
+        Duration::from_millis(self.time_ms as u64)
+    }
+
+    // how long until the replay needs drawing again, or None while it is paused
+    pub fn next_frame(&self) -> Option<Duration> {
+        self.playing.then_some(FRAME_TIME)
+    }
+
+    pub fn update(&mut self, dt: f64) {
+        if !self.playing {
+            return;
+        }
+        self.time_ms += dt * 1000. * self.speed;
+        let duration = self.replay.duration_ms() as f64;
+        if self.time_ms >= duration {
+            self.time_ms = duration;
+            self.playing = false;
+        }
+        self.go_to(self.due());
+    }
+
+    // the number of moves which were made by the current time
+    fn due(&self) -> usize {
+        self.replay
+            .events
+            .iter()
+            .take_while(|event| event.time_ms as f64 <= self.time_ms)
+            .count()
+    }
+
+    // makes moves on the game until the given number have been made
+    // (going backwards starts again from the beginning, as moves can't be taken back one at a time)
+    fn go_to(&mut self, applied: usize) {
+        if applied < self.applied {
+            self.game = self.replay.new_game();
+            self.applied = 0;
+        }
+        while self.applied < applied {
+            self.replay.events[self.applied]
+                .action
+                .apply(&mut self.game);
+            self.applied += 1;
+        }
+    }
+
+    pub fn toggle_playing(&mut self) {
+        // playing from the end starts again
+        if !self.playing && self.applied == self.replay.events.len() {
+            self.restart();
+        }
+        self.playing = !self.playing;
+    }
+
+    pub fn restart(&mut self) {
+        self.go_to(0);
+        self.time_ms = 0.;
+    }
+
+    pub fn step_forward(&mut self) {
+        self.playing = false;
+        if let Some(event) = self.replay.events.get(self.applied) {
+            self.time_ms = event.time_ms as f64;
+            self.go_to(self.applied + 1);
+        }
+    }
+
+    pub fn step_back(&mut self) {
+        self.playing = false;
+        let applied = self.applied.saturating_sub(1);
+        self.time_ms = match applied {
+            0 => 0.,
+            _ => self.replay.events[applied - 1].time_ms as f64,
+        };
+        self.go_to(applied);
+    }
+
+    // seeks to a fraction of the way through the replay
+    pub fn seek(&mut self, fraction: f64) {
+        self.time_ms = fraction.clamp(0., 1.) * self.replay.duration_ms() as f64;
+        self.go_to(self.due());
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = f64::min(self.speed * 2., MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = f64::max(self.speed / 2., MIN_SPEED);
+    }
+
+    // draws the progress bar in the border above the board, and the state of playback over the top of the board
+    pub fn draw(
+        &self,
+        bar_rect: [f64; 4],
+        board_rect: [f64; 4],
+        c: Context,
+        g: &mut G2d,
+        glyphs: &mut Glyphs,
+    ) {
+        let duration = self.replay.duration_ms() as f64;
+        let progress = if duration > 0. {
+            self.time_ms / duration
+        } else {
+            1.
+        };
+        rectangle([0.3, 0.3, 0.3, 1.], bar_rect, c.transform, g);
+        rectangle(
+            [0.9, 0.2, 0.2, 1.],
+            [
+                bar_rect[0],
+                bar_rect[1],
+                bar_rect[2] * progress,
+                bar_rect[3],
+            ],
+            c.transform,
+            g,
+        );
+
+        let status = format!(
+            "{} {:.1}/{:.1}s {}x",
+            if self.playing { "Playing" } else { "Paused" },
+            self.time_ms / 1000.,
+            duration / 1000.,
+            self.speed,
+        );
+        let lines = [
+            (status.as_str(), TEXT_SIZE - 4),
+            ("Space, arrows, Home, R to exit", TEXT_SIZE - 6),
+        ];
+        rectangle(
+            [0., 0., 0., 0.6],
+            [
+                board_rect[0],
+                board_rect[1],
+                f64::min(board_rect[2], 240.),
+                38.,
+            ],
+            c.transform,
+            g,
+        );
+        for (i, (line, size)) in lines.into_iter().enumerate() {
+            text::Text::new_color([1.; 4], size)
+                .draw(
+                    line,
+                    glyphs,
+                    &c.draw_state,
+                    c.transform
+                        .trans(board_rect[0] + 6., board_rect[1] + 16. + i as f64 * 16.),
+                    g,
+                )
+                .unwrap();
+        }
+    }
+}
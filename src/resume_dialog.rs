@@ -0,0 +1,72 @@
+// the dialog shown over the board when the window opens with an unfinished game saved from last time,
+// asking whether to carry on with it
+
+use minesweeper::game::*;
+use piston_window::*;
+
+use crate::consts::*;
+use crate::dialog::*;
+use crate::rects::*;
+
+// returns whether to resume the game (true) or start a new one (false) if one of the buttons was clicked
+pub fn resume_dialog_click(mouse_pos: [f64; 2], dialog_rect: [f64; 4]) -> Option<bool> {
+    let [resume_rect, new_game_rect] = button_rects(dialog_rect);
+    if mouse_intersects_rect(mouse_pos, resume_rect) {
+        Some(true)
+    } else if mouse_intersects_rect(mouse_pos, new_game_rect) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+pub fn draw_resume_dialog(
+    game: &Minesweeper,
+    dialog_rect: [f64; 4],
+    c: Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+) {
+    let transform = draw_dialog_frame(dialog_rect, c, g);
+
+    let lines = [
+        ("Resume last game?".to_string(), TEXT_SIZE, 28.),
+        (game.difficulty.to_string(), TEXT_SIZE - 4, 56.),
+        (
+            format!("{} seconds played", game.stopwatch.elapsed().as_secs()),
+            TEXT_SIZE - 4,
+            74.,
+        ),
+        (
+            "Enter: resume, N: new game".to_string(),
+            TEXT_SIZE - 4,
+            188.,
+        ),
+    ];
+    draw_lines(lines, transform, c, g, glyphs);
+
+    for (rect, label) in button_rects(dialog_rect)
+        .into_iter()
+        .zip(["Resume", "New game"])
+    {
+        rectangle([0.9, 0.9, 0.9, 1.], rect, c.transform, g);
+        Rectangle::new_border(DIALOG_TEXT_COLOR, 1.).draw(rect, &c.draw_state, c.transform, g);
+        text::Text::new_color(DIALOG_TEXT_COLOR, TEXT_SIZE - 2)
+            .draw(
+                label,
+                glyphs,
+                &c.draw_state,
+                c.transform.trans(rect[0] + 10., rect[1] + 23.),
+                g,
+            )
+            .unwrap();
+    }
+}
+
+// the resume and new game buttons, relative to the dialog
+fn button_rects(dialog_rect: [f64; 4]) -> [[f64; 4]; 2] {
+    [
+        [dialog_rect[0] + 16., dialog_rect[1] + 100., 112., 34.],
+        [dialog_rect[0] + 144., dialog_rect[1] + 100., 112., 34.],
+    ]
+}
@@ -0,0 +1,190 @@
+// saving an unfinished game to a file, so it can be carried on with later
+
+use crate::files::{self, FileError};
+use crate::game::*;
+use crate::replay::ReplayEvent;
+use crate::stopwatch::Stopwatch;
+use serde::{Deserialize, Serialize};
+use std::{path::Path, time::Duration};
+
+// the version of the saved game format, which should be increased whenever it changes
+pub const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    version: u32,
+    difficulty: Difficulty,
+    seed: u64,
+    no_guess: bool,
+    question_marks: bool,
+    first_go: bool,
+    mines_placed: bool,
+    // one character per tile, row by row: * for a mine and . for no mine
+    // (the numbers are worked out again from the mines)
+    mines: String,
+    // one character per tile: c for closed, f for flagged, q for a question mark and o for open
+    states: String,
+    mines_left: i64,
+    elapsed_ms: u64,
+    hints_used: u32,
+    practice: bool,
+    // the undo history and replay, so they carry on where they left off
+    moves: Vec<Move>,
+    undone: Vec<Move>,
+    events: Vec<ReplayEvent>,
+}
+
+impl Minesweeper {
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        let saved_game = SavedGame {
+            version: VERSION,
+            difficulty: self.difficulty,
+            seed: self.seed,
+            no_guess: self.no_guess,
+            question_marks: self.question_marks,
+            first_go: self.first_go,
+            mines_placed: self.mines_placed,
+            mines: self
+                .tiles
+                .iter()
+                .map(|tile| match tile.mines {
+                    TileMines::Mine => '*',
+                    TileMines::NoMine(_) => '.',
+                })
+                .collect(),
+            states: self
+                .tiles
+                .iter()
+                .map(|tile| match tile.state {
+                    TileState::Closed => 'c',
+                    TileState::Flagged => 'f',
+                    TileState::Questioned => 'q',
+                    TileState::Open => 'o',
+                })
+                .collect(),
+            mines_left: self.mines_left,
+            elapsed_ms: self.stopwatch.elapsed().as_millis() as u64,
+            hints_used: self.hints_used,
+            practice: self.practice,
+            moves: self.moves.clone(),
+            undone: self.undone.clone(),
+            events: self.events.clone(),
+        };
+        files::write_json(path, &saved_game)
+    }
+
+    // loads a saved game, with the stopwatch stopped at the time it was saved
+    // (start the stopwatch again to carry on timing if the game had started)
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        let saved_game: SavedGame = files::read_json(path, VERSION)?;
+        let dimensions = files::checked_dimensions(saved_game.difficulty)?;
+        let tiles = dimensions.x as usize * dimensions.y as usize;
+        let moves_in_board = saved_game
+            .moves
+            .iter()
+            .chain(saved_game.undone.iter())
+            .flat_map(|m| m.changes.iter())
+            .all(|&(i, _, _)| i < tiles);
+        // (the events are played on the board when the game is replayed)
+        let events_in_board = saved_game
+            .events
+            .iter()
+            .filter_map(|event| event.action.coord())
+            .all(|coord| coord.x < dimensions.x && coord.y < dimensions.y);
+        if saved_game.mines.chars().count() != tiles
+            || saved_game.states.chars().count() != tiles
+            || !moves_in_board
+            || !events_in_board
+        {
+            return Err(FileError::InvalidBoard);
+        }
+
+        let mut game = if saved_game.mines_placed {
+            let mut mines = Vec::new();
+            for (i, c) in saved_game.mines.chars().enumerate() {
+                match c {
+                    '*' => mines.push(Coord {
+                        x: (i % dimensions.x as usize) as u16,
+                        y: (i / dimensions.x as usize) as u16,
+                    }),
+                    '.' => {}
+                    _ => return Err(FileError::InvalidBoard),
+                }
+            }
+            let mut game = Minesweeper::with_layout(saved_game.difficulty, &mines);
+            game.seed = saved_game.seed;
+            game.no_guess = saved_game.no_guess;
+            game
+        } else {
+            Minesweeper::with_seed(saved_game.difficulty, saved_game.no_guess, saved_game.seed)
+        };
+        for (tile, c) in game.tiles.iter_mut().zip(saved_game.states.chars()) {
+            tile.state = match c {
+                'c' => TileState::Closed,
+                'f' => TileState::Flagged,
+                'q' => TileState::Questioned,
+                'o' => TileState::Open,
+                _ => return Err(FileError::InvalidBoard),
+            };
+        }
+        game.question_marks = saved_game.question_marks;
+        game.first_go = saved_game.first_go;
+        game.mines_left = saved_game.mines_left;
+        game.stopwatch = Stopwatch::with_elapsed(Duration::from_millis(saved_game.elapsed_ms));
+        game.hints_used = saved_game.hints_used;
+        game.practice = saved_game.practice;
+        game.moves = saved_game.moves;
+        game.undone = saved_game.undone;
+        game.events = saved_game.events;
+        // work out whether the game was won or lost
+        game.check_state();
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saving_and_loading_keeps_the_game() {
+        let mut game = Minesweeper::with_seed(Difficulty::Medium, false, 7);
+        game.question_marks = true;
+        game.open(Coord { x: 8, y: 8 });
+        let closed = game
+            .tiles
+            .iter()
+            .find(|tile| tile.state == TileState::Closed)
+            .unwrap()
+            .coord;
+        game.flag(closed);
+        game.stopwatch.stop();
+
+        let path =
+            std::env::temp_dir().join(format!("minesweeper-save-{}.json", std::process::id()));
+        game.save(&path).unwrap();
+        let loaded = Minesweeper::load(&path);
+        let _ = std::fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+
+        let tiles = |game: &Minesweeper| {
+            game.tiles
+                .iter()
+                .map(|tile| (tile.state, tile.mines == TileMines::Mine))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tiles(&loaded), tiles(&game));
+        assert_eq!(loaded.difficulty, game.difficulty);
+        assert_eq!(loaded.seed, game.seed);
+        assert!(loaded.question_marks);
+        assert_eq!(loaded.mines_left, game.mines_left);
+        assert_eq!(loaded.bbbv(), game.bbbv());
+        assert_eq!(loaded.events, game.events);
+        assert_eq!(
+            loaded.stopwatch.elapsed().as_millis(),
+            game.stopwatch.elapsed().as_millis()
+        );
+        assert!(loaded.state == GameState::Playing);
+        assert!(loaded.started());
+    }
+}
@@ -0,0 +1,170 @@
+// the fastest wins on each difficulty, with the name of who won them and when, which are kept between runs of the game
+
+use crate::files::{self, FileError};
+use crate::game::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// the version of the high scores file format, which should be increased whenever it changes
+pub const VERSION: u32 = 1;
+
+// how many scores are kept for each difficulty
+pub const MAX_SCORES: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub name: String,
+    pub time_ms: u64,
+    // used to break ties between equal times, as the faster player on the harder board ranks higher
+    pub bbbv_per_second: f64,
+    // when the game was won, in seconds since the unix epoch
+    pub date: u64,
+}
+impl Score {
+    // the score for a game that has been won
+    pub fn new(game: &Minesweeper, name: String, date: u64) -> Self {
+        let metrics = game.metrics();
+        Score {
+            name,
+            time_ms: metrics.time_ms,
+            bbbv_per_second: metrics.bbbv_per_second(),
+            date,
+        }
+    }
+
+    // whether this score ranks above another
+    fn beats(&self, other: &Score) -> bool {
+        self.time_ms < other.time_ms
+            || (self.time_ms == other.time_ms && self.bbbv_per_second > other.bbbv_per_second)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HighScores {
+    version: u32,
+    // each custom board size and number of mines has its own table
+    difficulties: Vec<(Difficulty, Vec<Score>)>,
+    // the name entered for the last high score, so it doesn't need typing in every time
+    pub last_name: String,
+}
+
+impl Default for HighScores {
+    fn default() -> Self {
+        HighScores {
+            version: VERSION,
+            difficulties: Vec::new(),
+            last_name: String::new(),
+        }
+    }
+}
+
+impl HighScores {
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        files::write_json(path, self)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        files::read_json(path, VERSION)
+    }
+
+    // the scores for a difficulty, best first
+    pub fn get(&self, difficulty: Difficulty) -> &[Score] {
+        self.difficulties
+            .iter()
+            .find(|(d, _)| *d == difficulty)
+            .map_or(&[], |(_, scores)| scores.as_slice())
+    }
+
+    // every difficulty which has scores
+    pub fn difficulties(&self) -> impl Iterator<Item = Difficulty> + '_ {
+        self.difficulties.iter().map(|(difficulty, _)| *difficulty)
+    }
+
+    // the place in the table a score would get, or None if it isn't good enough to be in it
+    pub fn rank(&self, difficulty: Difficulty, score: &Score) -> Option<usize> {
+        let scores = self.get(difficulty);
+        let rank = scores
+            .iter()
+            .position(|other| score.beats(other))
+            .unwrap_or(scores.len());
+        (rank < MAX_SCORES).then_some(rank)
+    }
+
+    // adds a score to the table, returning its place, or None if it wasn't good enough to be added
+    pub fn insert(&mut self, difficulty: Difficulty, score: Score) -> Option<usize> {
+        let rank = self.rank(difficulty, &score)?;
+        self.last_name = score.name.clone();
+        let scores = match self.difficulties.iter().position(|(d, _)| *d == difficulty) {
+            Some(i) => &mut self.difficulties[i].1,
+            None => {
+                self.difficulties.push((difficulty, Vec::new()));
+                &mut self.difficulties.last_mut().unwrap().1
+            }
+        };
+        scores.insert(rank, score);
+        scores.truncate(MAX_SCORES);
+        Some(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(name: &str, time_ms: u64, bbbv_per_second: f64) -> Score {
+        Score {
+            name: name.to_string(),
+            time_ms,
+            bbbv_per_second,
+            date: 0,
+        }
+    }
+
+    #[test]
+    fn equal_times_rank_by_3bv_per_second() {
+        let mut scores = HighScores::default();
+        assert_eq!(
+            scores.insert(Difficulty::Easy, score("a", 10_000, 2.)),
+            Some(0)
+        );
+        assert_eq!(
+            scores.insert(Difficulty::Easy, score("b", 10_000, 3.)),
+            Some(0)
+        );
+        // a tie on both goes after the scores already there
+        assert_eq!(
+            scores.rank(Difficulty::Easy, &score("c", 10_000, 2.)),
+            Some(2)
+        );
+        assert_eq!(
+            scores.insert(Difficulty::Easy, score("d", 9_000, 1.)),
+            Some(0)
+        );
+        let names: Vec<&str> = scores
+            .get(Difficulty::Easy)
+            .iter()
+            .map(|score| score.name.as_str())
+            .collect();
+        assert_eq!(names, ["d", "b", "a"]);
+        // the other difficulties have tables of their own
+        assert_eq!(
+            scores.rank(Difficulty::Hard, &score("e", 60_000, 1.)),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn only_the_best_scores_are_kept() {
+        let mut scores = HighScores::default();
+        for i in 0..MAX_SCORES as u64 {
+            scores.insert(Difficulty::Easy, score("a", 10_000 + i, 1.));
+        }
+        assert_eq!(scores.rank(Difficulty::Easy, &score("b", 20_000, 1.)), None);
+        assert_eq!(
+            scores.insert(Difficulty::Easy, score("b", 10_000, 1.)),
+            Some(1)
+        );
+        assert_eq!(scores.get(Difficulty::Easy).len(), MAX_SCORES);
+        assert_eq!(scores.last_name, "b");
+    }
+}
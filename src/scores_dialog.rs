@@ -0,0 +1,198 @@
+// the dialog shown over the board with the high score table for each difficulty,
+// which also asks for the player's name when they win a game fast enough to get in it
+
+use minesweeper::game::*;
+use minesweeper::scores::{HighScores, Score};
+use piston_window::*;
+
+use crate::consts::*;
+use crate::dialog::*;
+
+const MAX_NAME_LENGTH: usize = 12;
+
+pub struct ScoresDialog {
+    // the difficulties that can be looked through, which are the usual ones and any custom boards with scores
+    difficulties: Vec<Difficulty>,
+    selected: usize,
+    // the score of a game just won, which is added to the table once the player has typed their name into it
+    pending: Option<Score>,
+    // the place of the score that was just added, which is highlighted
+    highlighted: Option<usize>,
+}
+
+impl ScoresDialog {
+    // starts on the difficulty of the current game
+    pub fn new(scores: &HighScores, difficulty: Difficulty) -> Self {
+        let mut difficulties = vec![Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+        for d in scores.difficulties().chain([difficulty]) {
+            if !difficulties.contains(&d) {
+                difficulties.push(d);
+            }
+        }
+        ScoresDialog {
+            selected: difficulties.iter().position(|d| *d == difficulty).unwrap(),
+            difficulties,
+            pending: None,
+            highlighted: None,
+        }
+    }
+
+    // asks for a name for a new high score, starting with the name used last time
+    pub fn new_score(scores: &HighScores, difficulty: Difficulty, mut score: Score) -> Self {
+        score.name = scores.last_name.clone();
+        ScoresDialog {
+            pending: Some(score),
+            ..Self::new(scores, difficulty)
+        }
+    }
+
+    pub fn entering_name(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        if let Some(score) = &mut self.pending {
+            for c in text.chars().filter(|c| !c.is_control()) {
+                if score.name.chars().count() < MAX_NAME_LENGTH {
+                    score.name.push(c);
+                }
+            }
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(score) = &mut self.pending {
+            score.name.pop();
+        }
+    }
+
+    // adds the new score to the table, returning whether it was added
+    pub fn submit(&mut self, scores: &mut HighScores) -> bool {
+        match self.pending.take() {
+            Some(mut score) => {
+                score.name = score.name.trim().to_string();
+                if score.name.is_empty() {
+                    score.name = "Anonymous".to_string();
+                }
+                self.highlighted = scores.insert(self.difficulties[self.selected], score);
+                self.highlighted.is_some()
+            }
+            None => false,
+        }
+    }
+
+    pub fn next(&mut self) {
+        if self.pending.is_none() {
+            self.selected = (self.selected + 1) % self.difficulties.len();
+            self.highlighted = None;
+        }
+    }
+
+    pub fn draw(
+        &self,
+        scores: &HighScores,
+        dialog_rect: [f64; 4],
+        c: Context,
+        g: &mut G2d,
+        glyphs: &mut Glyphs,
+    ) {
+        let transform = draw_dialog_frame(dialog_rect, c, g);
+
+        let difficulty = self.difficulties[self.selected];
+        if let Some(score) = &self.pending {
+            let lines = [
+                ("New high score!".to_string(), TEXT_SIZE, 28.),
+                (difficulty.to_string(), TEXT_SIZE - 4, 56.),
+                (
+                    format!("{:.3} seconds", score.time_ms as f64 / 1000.),
+                    TEXT_SIZE - 4,
+                    74.,
+                ),
+                ("Name:".to_string(), TEXT_SIZE - 4, 122.),
+                ("Enter: save".to_string(), TEXT_SIZE - 4, 188.),
+            ];
+            draw_lines(lines, transform, c, g, glyphs);
+            let field_rect = [dialog_rect[0] + 70., dialog_rect[1] + 100., 180., 32.];
+            rectangle([1.; 4], field_rect, c.transform, g);
+            Rectangle::new_border(DIALOG_TEXT_COLOR, 1.).draw(
+                field_rect,
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+            text::Text::new_color(DIALOG_TEXT_COLOR, TEXT_SIZE - 2)
+                .draw(
+                    &format!("{}|", score.name),
+                    glyphs,
+                    &c.draw_state,
+                    c.transform.trans(field_rect[0] + 8., field_rect[1] + 22.),
+                    g,
+                )
+                .unwrap();
+            return;
+        }
+
+        draw_lines(
+            [(format!("High scores: {difficulty}"), TEXT_SIZE - 2, 24.)],
+            transform,
+            c,
+            g,
+            glyphs,
+        );
+        for (i, score) in scores.get(difficulty).iter().enumerate() {
+            let y = 46. + i as f64 * 13.;
+            if self.highlighted == Some(i) {
+                rectangle(
+                    [1., 1., 0.6, 1.],
+                    [
+                        dialog_rect[0] + 8.,
+                        dialog_rect[1] + y - 10.,
+                        dialog_rect[2] - 16.,
+                        13.,
+                    ],
+                    c.transform,
+                    g,
+                );
+            }
+            let columns = [
+                (format!("{}.", i + 1), 12.),
+                (score.name.clone(), 34.),
+                (format!("{:.3}s", score.time_ms as f64 / 1000.), 118.),
+                (format!("{:.2}", score.bbbv_per_second), 172.),
+                (format_date(score.date), 208.),
+            ];
+            for (column, x) in columns {
+                text::Text::new_color(DIALOG_TEXT_COLOR, TEXT_SIZE - 7)
+                    .draw(&column, glyphs, &c.draw_state, transform.trans(x, y), g)
+                    .unwrap();
+            }
+        }
+        draw_lines(
+            [("Tab: next, B: close", TEXT_SIZE - 4, 188.)],
+            transform,
+            c,
+            g,
+            glyphs,
+        );
+    }
+}
+
+// the date in year-month-day form from seconds since the unix epoch
+// (using the days from civil algorithm in reverse, to avoid a date library for one line of text)
+fn format_date(unix_secs: u64) -> String {
+    let days = (unix_secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year}-{month:02}-{day:02}")
+}
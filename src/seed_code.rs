@@ -0,0 +1,83 @@
+// codes for sharing a board by its seed, which also give the difficulty and whether the board was made without
+// guessing, as the same seed makes a different board with different settings:
+//
+//     hard:8046139                  a hard board
+//     30x20x99:no-guess:8046139     a 30x20 board with 99 mines, made without guessing
+
+use crate::game::*;
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SeedCode {
+    pub difficulty: Difficulty,
+    pub no_guess: bool,
+    pub seed: u64,
+}
+
+impl SeedCode {
+    // the code of a game, which makes the same board again
+    pub fn of(game: &Minesweeper) -> Self {
+        SeedCode {
+            difficulty: game.difficulty,
+            no_guess: game.no_guess,
+            seed: game.seed,
+        }
+    }
+
+    // reads a code, or returns None if the text isn't one
+    pub fn parse(code: &str) -> Option<Self> {
+        let parts: Vec<&str> = code.trim().split(':').collect();
+        let (&difficulty, rest) = parts.split_first()?;
+        let (&seed, options) = rest.split_last()?;
+        let difficulty = match difficulty.to_lowercase().as_str() {
+            "easy" => Difficulty::Easy,
+            "medium" => Difficulty::Medium,
+            "hard" => Difficulty::Hard,
+            custom => {
+                let size: Vec<&str> = custom.split('x').collect();
+                let [width, height, mines] = size.as_slice() else {
+                    return None;
+                };
+                Difficulty::custom(
+                    width.parse().ok()?,
+                    height.parse().ok()?,
+                    mines.parse().ok()?,
+                )
+                .ok()?
+            }
+        };
+        let no_guess = match options {
+            [] => false,
+            ["no-guess"] => true,
+            _ => return None,
+        };
+        Some(SeedCode {
+            difficulty,
+            no_guess,
+            seed: seed.parse().ok()?,
+        })
+    }
+
+    pub fn new_game(&self) -> Minesweeper {
+        Minesweeper::with_seed(self.difficulty, self.no_guess, self.seed)
+    }
+}
+
+impl fmt::Display for SeedCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.difficulty {
+            Difficulty::Easy => write!(f, "easy")?,
+            Difficulty::Medium => write!(f, "medium")?,
+            Difficulty::Hard => write!(f, "hard")?,
+            Difficulty::Custom {
+                width,
+                height,
+                mines,
+            } => write!(f, "{width}x{height}x{mines}")?,
+        }
+        if self.no_guess {
+            write!(f, ":no-guess")?;
+        }
+        write!(f, ":{}", self.seed)
+    }
+}
@@ -0,0 +1,52 @@
+// the options the player has chosen for new games, which are kept between runs of the game
+// (changing one doesn't change the game in progress, only the games started after it)
+
+use crate::files::{self, FileError};
+use crate::game::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// the version of the settings file format, which should be increased whenever it changes
+pub const VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Settings {
+    version: u32,
+    // whether boards are generated so they can be solved without guessing
+    pub no_guess: bool,
+    // whether flagging a flagged tile puts a question mark on it before closing it again
+    pub question_marks: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: VERSION,
+            no_guess: false,
+            question_marks: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        files::write_json(path, self)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        files::read_json(path, VERSION)
+    }
+
+    // a new game with these settings
+    pub fn new_game(&self, difficulty: Difficulty) -> Minesweeper {
+        self.game_with_seed(difficulty, rand::random())
+    }
+
+    // a new game with these settings from a seed, which gives the same board as any other game with the same seed
+    // and settings
+    pub fn game_with_seed(&self, difficulty: Difficulty, seed: u64) -> Minesweeper {
+        let mut game = Minesweeper::with_seed(difficulty, self.no_guess, seed);
+        game.question_marks = self.question_marks;
+        game
+    }
+}
@@ -0,0 +1,443 @@
+// a logical solver which works out which tiles are definitely safe and which are definitely mines,
+// using only what the player would be able to see (it never looks at the mines of tiles which aren't open)
+
+use crate::game::*;
+
+// the tiles which the solver has proven to be safe or mines
+#[derive(Default, Debug)]
+pub struct Deductions {
+    pub safe: Vec<Coord>,
+    pub mines: Vec<Coord>,
+}
+
+// solves as much of the board as possible from the open tiles and the total number of mines
+// flags are ignored, as they are only the player's guesses and may be wrong
+pub fn solve(game: &Minesweeper) -> Deductions {
+    solve_with_knowledge(game).0
+}
+
+// also returns what is known about every tile once the board has been solved as much as possible
+pub(crate) fn solve_with_knowledge(game: &Minesweeper) -> (Deductions, Vec<Knowledge>) {
+    let knowledge = game
+        .tiles
+        .iter()
+        .map(|tile| match (&tile.state, &tile.mines) {
+            (TileState::Open, TileMines::NoMine(n)) => Knowledge::Open(*n),
+            // an open mine (if the game has been lost)
+            (TileState::Open, TileMines::Mine) => Knowledge::Mine,
+            _ => Knowledge::Unknown,
+        })
+        .collect();
+
+    let mut solver = Solver::new(game.dimensions, game.mines(), knowledge);
+    let mut deductions = Deductions::default();
+    loop {
+        let found = solver.next_deductions();
+        if found.is_empty() {
+            return (deductions, solver.knowledge);
+        }
+        for (coord, is_mine) in found {
+            if is_mine {
+                solver.set(coord, Knowledge::Mine);
+                deductions.mines.push(coord);
+            } else {
+                solver.set(coord, Knowledge::Safe);
+                deductions.safe.push(coord);
+            }
+        }
+    }
+}
+
+// what the solver knows about each tile
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) enum Knowledge {
+    Unknown,
+    Mine,
+    // a tile which is known to be safe, but hasn't been opened so its number isn't known
+    Safe,
+    // an open tile and the number of mines around it
+    Open(u8),
+}
+
+// the unknown tiles around an open tile, and how many of them must be mines
+struct Constraint {
+    tiles: Vec<Coord>,
+    mines: u8,
+}
+
+pub(crate) fn index(dimensions: Coord, coord: Coord) -> usize {
+    coord.y as usize * dimensions.x as usize + coord.x as usize
+}
+
+// the solver only looks again at the open tiles around tiles that have changed,
+// so that solving a big board doesn't mean checking every tile over and over again
+pub(crate) struct Solver {
+    dimensions: Coord,
+    mines: u32,
+    knowledge: Vec<Knowledge>,
+    // open tiles which need checking on their own, and in pairs with the tiles near them
+    to_check: Vec<Coord>,
+    to_check_pairs: Vec<Coord>,
+    // whether each tile is already in the lists above, so tiles aren't added twice
+    queued: Vec<bool>,
+    queued_pairs: Vec<bool>,
+}
+
+impl Solver {
+    pub(crate) fn new(dimensions: Coord, mines: u32, knowledge: Vec<Knowledge>) -> Self {
+        let mut solver = Solver {
+            dimensions,
+            mines,
+            queued: vec![false; knowledge.len()],
+            queued_pairs: vec![false; knowledge.len()],
+            knowledge,
+            to_check: Vec::new(),
+            to_check_pairs: Vec::new(),
+        };
+        for y in 0..dimensions.y {
+            for x in 0..dimensions.x {
+                solver.queue(Coord { x, y });
+            }
+        }
+        solver
+    }
+
+    pub(crate) fn get(&self, coord: Coord) -> Knowledge {
+        self.knowledge[index(self.dimensions, coord)]
+    }
+
+    pub(crate) fn set(&mut self, coord: Coord, knowledge: Knowledge) {
+        self.knowledge[index(self.dimensions, coord)] = knowledge;
+        // the tile itself and the tiles around it have changed, so will need checking again
+        self.queue(coord);
+        for neighbour in tiles_around(self.dimensions, coord, 1) {
+            self.queue(neighbour);
+        }
+    }
+
+    fn queue(&mut self, coord: Coord) {
+        let i = index(self.dimensions, coord);
+        if !matches!(self.knowledge[i], Knowledge::Open(_)) {
+            return;
+        }
+        if !self.queued[i] {
+            self.queued[i] = true;
+            self.to_check.push(coord);
+        }
+        if !self.queued_pairs[i] {
+            self.queued_pairs[i] = true;
+            self.to_check_pairs.push(coord);
+        }
+    }
+
+    // the constraint of an open tile which has unknown tiles around it
+    fn constraint(&self, coord: Coord) -> Option<Constraint> {
+        let Knowledge::Open(n) = self.get(coord) else {
+            return None;
+        };
+        let mut tiles = Vec::new();
+        let mut known_mines = 0;
+        for neighbour in tiles_around(self.dimensions, coord, 1) {
+            match self.get(neighbour) {
+                Knowledge::Unknown => tiles.push(neighbour),
+                Knowledge::Mine => known_mines += 1,
+                _ => {}
+            }
+        }
+        if tiles.is_empty() {
+            return None;
+        }
+        Some(Constraint {
+            tiles,
+            mines: n.saturating_sub(known_mines),
+        })
+    }
+
+    // returns the next tiles which can be proven to be safe (false) or mines (true),
+    // or nothing if there isn't enough information to prove anything else
+    pub(crate) fn next_deductions(&mut self) -> Vec<(Coord, bool)> {
+        while let Some(coord) = self.to_check.pop() {
+            self.queued[index(self.dimensions, coord)] = false;
+            if let Some(found) = self.constraint(coord).and_then(|c| single_deductions(&c)) {
+                return found;
+            }
+        }
+        // only look at pairs of tiles once the simpler rule can't find anything, as it is slower
+        while let Some(a_coord) = self.to_check_pairs.pop() {
+            self.queued_pairs[index(self.dimensions, a_coord)] = false;
+            let Some(a) = self.constraint(a_coord) else {
+                continue;
+            };
+            for b_coord in tiles_around(self.dimensions, a_coord, 2) {
+                let Some(b) = self.constraint(b_coord) else {
+                    continue;
+                };
+                if let Some(found) = pair_deductions(&a, &b).or_else(|| pair_deductions(&b, &a)) {
+                    // the rest of the pairs with this tile haven't been checked yet
+                    self.queue(a_coord);
+                    return found;
+                }
+            }
+        }
+        self.mine_count_deductions()
+    }
+
+    // once all the mines have been found, every other unknown tile is safe,
+    // and if there are only as many unknown tiles left as there are mines left, they are all mines
+    fn mine_count_deductions(&self) -> Vec<(Coord, bool)> {
+        let known_mines = self
+            .knowledge
+            .iter()
+            .filter(|&&k| k == Knowledge::Mine)
+            .count();
+        let unknown: Vec<Coord> = (0..self.knowledge.len())
+            .filter(|&i| self.knowledge[i] == Knowledge::Unknown)
+            .map(|i| Coord {
+                x: (i % self.dimensions.x as usize) as u16,
+                y: (i / self.dimensions.x as usize) as u16,
+            })
+            .collect();
+        let mines_left = (self.mines as usize).saturating_sub(known_mines);
+        if unknown.is_empty() || (mines_left != 0 && mines_left != unknown.len()) {
+            return Vec::new();
+        }
+        let is_mine = mines_left != 0;
+        unknown.into_iter().map(|coord| (coord, is_mine)).collect()
+    }
+}
+
+// if a tile needs no more mines, all the unknown tiles around it are safe,
+// and if it needs as many mines as it has unknown tiles around it, they are all mines
+fn single_deductions(constraint: &Constraint) -> Option<Vec<(Coord, bool)>> {
+    if constraint.mines == 0 {
+        Some(constraint.tiles.iter().map(|&tile| (tile, false)).collect())
+    } else if constraint.mines as usize == constraint.tiles.len() {
+        Some(constraint.tiles.iter().map(|&tile| (tile, true)).collect())
+    } else {
+        None
+    }
+}
+
+// compares the constraints of two open tiles which share some unknown tiles
+fn pair_deductions(a: &Constraint, b: &Constraint) -> Option<Vec<(Coord, bool)>> {
+    let only_a: Vec<Coord> = a
+        .tiles
+        .iter()
+        .filter(|t| !b.tiles.contains(t))
+        .copied()
+        .collect();
+    let only_b: Vec<Coord> = b
+        .tiles
+        .iter()
+        .filter(|t| !a.tiles.contains(t))
+        .copied()
+        .collect();
+    if only_b.is_empty() || only_a.len() == a.tiles.len() {
+        // b's tiles are all around a (which is handled when comparing the other way round),
+        // or they have no tiles in common
+        return None;
+    }
+
+    if b.mines as i32 - a.mines as i32 == only_b.len() as i32 {
+        // b needs so many more mines than a that all of the tiles only around b must be mines,
+        // which means the shared tiles have all of a's mines, so the tiles only around a are safe
+        let mines = only_b.iter().map(|&tile| (tile, true));
+        Some(
+            mines
+                .chain(only_a.iter().map(|&tile| (tile, false)))
+                .collect(),
+        )
+    } else if only_a.is_empty() && a.mines == b.mines {
+        // all of a's tiles are around b, and they already have all of b's mines
+        Some(only_b.iter().map(|&tile| (tile, false)).collect())
+    } else {
+        None
+    }
+}
+
+// checks whether the whole board can be cleared from the start tile without ever having to guess
+pub(crate) fn is_solvable(game: &Minesweeper, start_coord: Coord) -> bool {
+    let dimensions = game.dimensions;
+    let mut solver = Solver::new(
+        dimensions,
+        game.mines(),
+        vec![Knowledge::Unknown; game.tiles.len()],
+    );
+    let safe_tiles = game
+        .tiles
+        .iter()
+        .filter(|tile| tile.mines != TileMines::Mine)
+        .count();
+    let mut opened = 0;
+
+    let mut to_open = vec![start_coord];
+    loop {
+        // open the tiles the same way the player would, including opening around tiles with no mines around them
+        while let Some(coord) = to_open.pop() {
+            if matches!(solver.get(coord), Knowledge::Open(_) | Knowledge::Mine) {
+                continue;
+            }
+            let TileMines::NoMine(n) = game.get_tile(coord).mines else {
+                // the solver should never think a mine is safe
+                return false;
+            };
+            solver.set(coord, Knowledge::Open(n));
+            opened += 1;
+            if n == 0 {
+                to_open.extend(tiles_around(dimensions, coord, 1));
+            }
+        }
+        if opened == safe_tiles {
+            return true;
+        }
+
+        let found = solver.next_deductions();
+        if found.is_empty() {
+            // stuck, so the player would have to guess
+            return false;
+        }
+        for (coord, is_mine) in found {
+            if is_mine {
+                solver.set(coord, Knowledge::Mine);
+            } else {
+                to_open.push(coord);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    // a board from rows of * for a mine, . for a closed safe tile and o for an open safe tile
+    pub(crate) fn board(rows: &[&str]) -> Minesweeper {
+        let width = rows[0].len() as u16;
+        let height = rows.len() as u16;
+        let coords = || {
+            rows.iter().enumerate().flat_map(|(y, row)| {
+                row.chars().enumerate().map(move |(x, c)| {
+                    let coord = Coord {
+                        x: x as u16,
+                        y: y as u16,
+                    };
+                    (coord, c)
+                })
+            })
+        };
+        let mines: Vec<Coord> = coords()
+            .filter(|&(_, c)| c == '*')
+            .map(|(coord, _)| coord)
+            .collect();
+        let difficulty = Difficulty::Custom {
+            width,
+            height,
+            mines: mines.len() as u32,
+        };
+        let mut game = Minesweeper::with_layout(difficulty, &mines);
+        for (coord, c) in coords() {
+            if c == 'o' {
+                game.get_tile_mut(coord).state = TileState::Open;
+            }
+        }
+        game
+    }
+
+    // a small board with some mines, where some of the safe tiles are open
+    pub(crate) fn random_board(rng: &mut ChaCha8Rng) -> Minesweeper {
+        let rows: Vec<String> = (0..3)
+            .map(|_| {
+                (0..5)
+                    .map(|_| match rng.gen_range(0..10) {
+                        0..=2 => '*',
+                        3..=5 => '.',
+                        _ => 'o',
+                    })
+                    .collect()
+            })
+            .collect();
+        let rows: Vec<&str> = rows.iter().map(|row| row.as_str()).collect();
+        board(&rows)
+    }
+
+    // every arrangement of the mines in the closed tiles which fits the open numbers, found by trying them all
+    pub(crate) fn arrangements(game: &Minesweeper) -> Vec<Vec<bool>> {
+        let closed: Vec<usize> = (0..game.tiles.len())
+            .filter(|&i| game.tiles[i].state != TileState::Open)
+            .collect();
+        let mut found = Vec::new();
+        for bits in 0u32..1 << closed.len() {
+            if bits.count_ones() != game.mines() {
+                continue;
+            }
+            let mut mines = vec![false; game.tiles.len()];
+            for (j, &i) in closed.iter().enumerate() {
+                mines[i] = bits & (1 << j) != 0;
+            }
+            let fits = game.tiles.iter().all(|tile| match tile.mines {
+                TileMines::NoMine(n) if tile.state == TileState::Open => {
+                    let around = game
+                        .iter_neighbours(tile.coord)
+                        .filter(|&neighbour| mines[index(game.dimensions, neighbour)])
+                        .count();
+                    around == n as usize
+                }
+                _ => true,
+            });
+            if fits {
+                found.push(mines);
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn single_tile_deductions() {
+        // each 1 only has the mine around it
+        let deductions = solve(&board(&["oo", "o*"]));
+        assert_eq!(deductions.mines, vec![Coord { x: 1, y: 1 }]);
+        assert!(deductions.safe.is_empty());
+
+        // the 0s make the tile next to them safe, which leaves the mine as the only tile left
+        let deductions = solve(&board(&["oo.*"]));
+        assert_eq!(deductions.safe, vec![Coord { x: 2, y: 0 }]);
+        assert_eq!(deductions.mines, vec![Coord { x: 3, y: 0 }]);
+    }
+
+    #[test]
+    fn pair_deductions_solve_one_two_one() {
+        // none of the 1 2 1 along the bottom can be solved on its own, but comparing them finds the mines
+        let deductions = solve(&board(&["*.*", "ooo"]));
+        let mut mines = deductions.mines.clone();
+        mines.sort_by_key(|coord| coord.x);
+        assert_eq!(mines, vec![Coord { x: 0, y: 0 }, Coord { x: 2, y: 0 }]);
+        assert_eq!(deductions.safe, vec![Coord { x: 1, y: 0 }]);
+    }
+
+    #[test]
+    fn deductions_are_sound() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..300 {
+            let game = random_board(&mut rng);
+            let arrangements = arrangements(&game);
+            let deductions = solve(&game);
+            // anything the solver finds must be the same in every arrangement the player can't rule out
+            for (coords, is_mine) in [(&deductions.safe, false), (&deductions.mines, true)] {
+                for &coord in coords {
+                    let i = index(game.dimensions, coord);
+                    assert!(arrangements.iter().all(|mines| mines[i] == is_mine));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn solvable_boards() {
+        // opening the corner opens up to the 1, which shows where the mine is
+        assert!(is_solvable(&board(&["...*."]), Coord { x: 0, y: 0 }));
+        // the 1 could be from any of the other three tiles
+        assert!(!is_solvable(&board(&["..", ".*"]), Coord { x: 0, y: 0 }));
+    }
+}
@@ -1,28 +1,41 @@
 // functions which return an iterator over a collection of sprites to be drawn
 use piston_window::*;
+use std::time::Duration;
 
+use crate::board_view::*;
 use crate::consts::*;
-use crate::game::*;
 use crate::textures::*;
+use minesweeper::game::*;
+use minesweeper::probability::Probabilities;
 
 pub fn tile_sprites<'a>(
     game: &Minesweeper,
     textures: &'a GameTextures,
+    board_view: &BoardView,
+    // if given, closed tiles are tinted from green to red by their chance of being a mine
+    probabilities: Option<&Probabilities>,
+    // closed tiles which are shown pressed in, as a mouse button is held over them
+    pressed: &[Coord],
 ) -> impl Iterator<Item = (Image, &'a Texture<gfx_device_gl::Resources>)> {
-    let mut sprites = Vec::with_capacity(game.dimensions.x as usize * game.dimensions.y as usize);
-    for x in 0..game.dimensions.x {
-        for y in 0..game.dimensions.y {
+    // only the tiles which are visible are drawn, so that huge boards don't have to draw every tile
+    let (min, max) = board_view.visible_coords();
+    let mut sprites =
+        Vec::with_capacity((max.x - min.x + 1) as usize * (max.y - min.y + 1) as usize);
+    for x in min.x..=max.x {
+        for y in min.y..=max.y {
             let coord = Coord { x, y };
 
-            let image = Image::new().rect([
-                x as f64 * TILE_SIZE + BORDER_SIZE,
-                y as f64 * TILE_SIZE + BORDER_MIDDLE_POS + BORDER_SIZE,
-                TILE_SIZE,
-                TILE_SIZE,
-            ]);
+            let mut image = Image::new().rect(board_view.tile_rect(coord));
+            if let Some(p) = probabilities.and_then(|probabilities| probabilities.get(coord)) {
+                image = image.color(heat_map_color(p));
+            }
             let mut texture = match game.get_tile(coord).state {
+                TileState::Closed | TileState::Questioned if pressed.contains(&coord) => {
+                    &textures.tile.pressed
+                }
                 TileState::Closed => &textures.tile.closed,
                 TileState::Flagged => &textures.tile.flagged,
+                TileState::Questioned => &textures.tile.questioned,
                 TileState::Open => match game.get_tile(coord).mines {
                     TileMines::NoMine(n) => &textures.tile.number[n as usize],
                     TileMines::Mine => &textures.tile.mine,
@@ -52,44 +65,55 @@ pub fn tile_sprites<'a>(
     sprites.into_iter()
 }
 
-pub fn border_sprites<'a>(
-    window_size: &Size,
-    textures: &'a GameTextures,
-) -> impl Iterator<Item = (Image, &'a Texture<gfx_device_gl::Resources>)> {
+// green for a tile which is definitely safe, through yellow, to red for a tile which is definitely a mine
+fn heat_map_color(p: f64) -> [f32; 4] {
+    let p = p as f32;
+    [
+        0.5 + (p * 2.).min(1.) * 0.5,
+        1. - ((p - 0.5) * 2.).max(0.) * 0.6,
+        0.5 - p * 0.1,
+        1.,
+    ]
+}
+
+pub fn border_sprites(
+    window_size: [f64; 2],
+    textures: &GameTextures,
+) -> impl Iterator<Item = (Image, &Texture<gfx_device_gl::Resources>)> {
     vec![
         // top
         (
-            Image::new().rect([0., 0., window_size.width, BORDER_SIZE]),
+            Image::new().rect([0., 0., window_size[0], BORDER_SIZE]),
             &textures.border.horizontal,
         ),
         // bottom
         (
             Image::new().rect([
                 0.,
-                window_size.height - BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
-                window_size.width,
+                window_size[1] - BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
+                window_size[0],
                 BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
             ]),
             &textures.border.horizontal_wide,
         ),
         // left
         (
-            Image::new().rect([0., 0., BORDER_SIZE, window_size.height]),
+            Image::new().rect([0., 0., BORDER_SIZE, window_size[1]]),
             &textures.border.vertical,
         ),
         // right
         (
             Image::new().rect([
-                window_size.width - BORDER_SIZE,
+                window_size[0] - BORDER_SIZE,
                 0.,
                 BORDER_SIZE,
-                window_size.height,
+                window_size[1],
             ]),
             &textures.border.vertical,
         ),
         // middle
         (
-            Image::new().rect([0., BORDER_MIDDLE_POS, window_size.width, BORDER_SIZE]),
+            Image::new().rect([0., BORDER_MIDDLE_POS, window_size[0], BORDER_SIZE]),
             &textures.border.horizontal,
         ),
         // top left
@@ -99,19 +123,14 @@ pub fn border_sprites<'a>(
         ),
         // top right
         (
-            Image::new().rect([
-                window_size.width - BORDER_SIZE,
-                0.,
-                BORDER_SIZE,
-                BORDER_SIZE,
-            ]),
+            Image::new().rect([window_size[0] - BORDER_SIZE, 0., BORDER_SIZE, BORDER_SIZE]),
             &textures.border.top_right,
         ),
         // bottom left
         (
             Image::new().rect([
                 0.,
-                window_size.height - BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
+                window_size[1] - BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
                 BORDER_SIZE,
                 BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
             ]),
@@ -120,8 +139,8 @@ pub fn border_sprites<'a>(
         // bottom right
         (
             Image::new().rect([
-                window_size.width - BORDER_SIZE,
-                window_size.height - BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
+                window_size[0] - BORDER_SIZE,
+                window_size[1] - BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
                 BORDER_SIZE,
                 BORDER_SIZE * BORDER_WIDE_SCALE_FACTOR,
             ]),
@@ -135,7 +154,7 @@ pub fn border_sprites<'a>(
         // middle right
         (
             Image::new().rect([
-                window_size.width - BORDER_SIZE,
+                window_size[0] - BORDER_SIZE,
                 BORDER_MIDDLE_POS,
                 BORDER_SIZE,
                 BORDER_SIZE,
@@ -150,33 +169,58 @@ pub fn face_button_sprite<'a>(
     game: &Minesweeper,
     textures: &'a GameTextures,
     face_button_rect: [f64; 4],
+    // whether a mouse button is held on the board, which makes the face look surprised
+    held: bool,
 ) -> (Image, &'a Texture<gfx_device_gl::Resources>) {
     let image = Image::new().rect(face_button_rect);
     let texture = match game.state {
         GameState::Lost => &textures.face_buttons.lost,
         GameState::Won => &textures.face_buttons.won,
+        GameState::Playing if held => &textures.face_buttons.surprised,
         GameState::Playing => &textures.face_buttons.normal,
     };
     (image, texture)
 }
 
+// the hint button is shown pressed while a hint is being shown
+pub fn hint_button_sprite(
+    hint: Option<Hint>,
+    textures: &GameTextures,
+    hint_button_rect: [f64; 4],
+) -> (Image, &Texture<gfx_device_gl::Resources>) {
+    let image = Image::new().rect(hint_button_rect);
+    let texture = match hint {
+        Some(_) => &textures.hint_button.pressed,
+        None => &textures.hint_button.normal,
+    };
+    (image, texture)
+}
+
 pub fn difficulty_buttons_sprites<'a>(
     game: &Minesweeper,
     textures: &'a GameTextures,
-    difficulty_buttons_rects: [[f64; 4]; 3],
+    difficulty_buttons_rects: [[f64; 4]; 4],
 ) -> impl Iterator<Item = (Image, &'a Texture<gfx_device_gl::Resources>)> {
+    let selected = match game.difficulty {
+        Difficulty::Easy => 0,
+        Difficulty::Medium => 1,
+        Difficulty::Hard => 2,
+        Difficulty::Custom { .. } => 3,
+    };
     let mut sprites = Vec::new();
     for (i, button_rect) in difficulty_buttons_rects.iter().enumerate() {
         let image = Image::new().rect(*button_rect);
         let mut texture = match i {
             1 => &textures.difficulty_buttons.medium,
             2 => &textures.difficulty_buttons.hard,
+            3 => &textures.difficulty_buttons.custom,
             _ => &textures.difficulty_buttons.easy,
         };
-        if game.difficulty as usize == i {
+        if selected == i {
             texture = match i {
                 1 => &textures.difficulty_buttons.medium_pressed,
                 2 => &textures.difficulty_buttons.hard_pressed,
+                3 => &textures.difficulty_buttons.custom_pressed,
                 _ => &textures.difficulty_buttons.easy_pressed,
             }
         }
@@ -185,68 +229,113 @@ pub fn difficulty_buttons_sprites<'a>(
     sprites.into_iter()
 }
 
-fn num_display_sprites(
-    textures: &GameTextures,
+// a number display made of the digits, decimal points and minus signs in some text, with the background
+// put together from its parts so that it fits them all
+pub fn num_display_sprites<'a>(
+    textures: &'a GameTextures,
     num_display_rect: [f64; 4],
-    num: i32,
-) -> impl Iterator<Item = (Image, &Texture<gfx_device_gl::Resources>)> {
-    let mut sprites = Vec::new();
-    let num = if num < 0 { 0 } else { num };
-    // the background
-    sprites.push((
-        Image::new().rect(num_display_rect),
-        &textures.number_display.background,
-    ));
-
-    let hundreds_rect = [
-        num_display_rect[0] + (14. / 274.) * num_display_rect[2],
-        num_display_rect[1] + (14. / 170.) * num_display_rect[3],
-        (74. / 274.) * num_display_rect[2],
-        (142. / 170.) * num_display_rect[3],
-    ];
-    sprites.push((
-        Image::new().rect(hundreds_rect),
-        &textures.number_display.numbers[((num / 100) % 10) as usize],
-    ));
-
-    let tens_rect = [
-        num_display_rect[0] + (100. / 274.) * num_display_rect[2],
-        num_display_rect[1] + (14. / 170.) * num_display_rect[3],
-        (74. / 274.) * num_display_rect[2],
-        (142. / 170.) * num_display_rect[3],
-    ];
-    sprites.push((
-        Image::new().rect(tens_rect),
-        &textures.number_display.numbers[((num / 10) % 10) as usize],
-    ));
+    text: &str,
+) -> impl Iterator<Item = (Image, &'a Texture<gfx_device_gl::Resources>)> {
+    // the size of a pixel of the background texture in the window
+    let scale = num_display_rect[3] / NUM_DISPLAY_TEXTURE_HEIGHT;
+    let background = &textures.number_display.background;
+    // a part of the background texture, drawn at x across the display (in the texture's pixels) and stretched to a width
+    let background_part = |src_x: f64, src_width: f64, x: f64, width: f64| {
+        let image = Image::new()
+            .src_rect([src_x, 0., src_width, NUM_DISPLAY_TEXTURE_HEIGHT])
+            .rect([
+                num_display_rect[0] + x * scale,
+                num_display_rect[1],
+                width * scale,
+                num_display_rect[3],
+            ]);
+        (image, background)
+    };
+    // the parts of the texture, which has three digits: a digit which isn't lit, the gap after it, and the right edge
+    let digit_x = NUM_DISPLAY_TEXTURE_EDGE;
+    let gap_x = NUM_DISPLAY_TEXTURE_EDGE + NUM_DISPLAY_TEXTURE_DIGIT;
+    let edge_x =
+        NUM_DISPLAY_TEXTURE_EDGE + NUM_DISPLAY_TEXTURE_DIGIT * 3. + NUM_DISPLAY_TEXTURE_GAP * 2.;
 
-    let units_rect = [
-        num_display_rect[0] + (186. / 274.) * num_display_rect[2],
-        num_display_rect[1] + (14. / 170.) * num_display_rect[3],
-        (74. / 274.) * num_display_rect[2],
-        (142. / 170.) * num_display_rect[3],
-    ];
-    sprites.push((
-        Image::new().rect(units_rect),
-        &textures.number_display.numbers[(num % 10) as usize],
+    let mut sprites = vec![background_part(
+        0.,
+        NUM_DISPLAY_TEXTURE_EDGE,
+        0.,
+        NUM_DISPLAY_TEXTURE_EDGE,
+    )];
+    let mut glyphs = Vec::new();
+    let mut x = NUM_DISPLAY_TEXTURE_EDGE;
+    for (i, c) in text.chars().enumerate() {
+        if i > 0 {
+            sprites.push(background_part(
+                gap_x,
+                NUM_DISPLAY_TEXTURE_GAP,
+                x,
+                NUM_DISPLAY_TEXTURE_GAP,
+            ));
+            x += NUM_DISPLAY_TEXTURE_GAP;
+        }
+        // digits fill the room for them apart from the edges, and points sit at the bottom
+        let (texture, width, glyph_rect) = match c {
+            '.' => (
+                &textures.number_display.point,
+                NUM_DISPLAY_TEXTURE_POINT,
+                [x + 1., 140., 16., 16.],
+            ),
+            '-' => (
+                &textures.number_display.minus,
+                NUM_DISPLAY_TEXTURE_DIGIT,
+                [x, 14., NUM_DISPLAY_TEXTURE_DIGIT, 142.],
+            ),
+            _ => (
+                &textures.number_display.numbers[c.to_digit(10).unwrap_or(0) as usize],
+                NUM_DISPLAY_TEXTURE_DIGIT,
+                [x, 14., NUM_DISPLAY_TEXTURE_DIGIT, 142.],
+            ),
+        };
+        sprites.push(match c {
+            '.' => background_part(gap_x, NUM_DISPLAY_TEXTURE_GAP, x, width),
+            _ => background_part(digit_x, NUM_DISPLAY_TEXTURE_DIGIT, x, width),
+        });
+        glyphs.push((
+            Image::new().rect([
+                num_display_rect[0] + glyph_rect[0] * scale,
+                num_display_rect[1] + glyph_rect[1] * scale,
+                glyph_rect[2] * scale,
+                glyph_rect[3] * scale,
+            ]),
+            texture,
+        ));
+        x += width;
+    }
+    sprites.push(background_part(
+        edge_x,
+        NUM_DISPLAY_TEXTURE_EDGE,
+        x,
+        NUM_DISPLAY_TEXTURE_EDGE,
     ));
-
+    // the digits are drawn over the whole background
+    sprites.extend(glyphs);
     sprites.into_iter()
 }
 
-pub fn stopwatch_sprites<'a>(
-    game: &Minesweeper,
-    textures: &'a GameTextures,
-    stopwatch_rect: [f64; 4],
-) -> impl Iterator<Item = (Image, &'a Texture<gfx_device_gl::Resources>)> {
-    let time = game.stopwatch.elapsed().as_secs();
-    num_display_sprites(textures, stopwatch_rect, time as i32)
+// the time on the stopwatch, in whole seconds or with some decimal places, with at least three digits before the point
+// (a time over 999 seconds gets more digits rather than going back to 0)
+pub fn stopwatch_text(time: Duration, decimals: u32) -> String {
+    let seconds = time.as_secs();
+    if decimals == 0 {
+        return format!("{seconds:03}");
+    }
+    let fraction = time.subsec_millis() / 10u32.pow(3 - decimals);
+    format!("{seconds:03}.{fraction:0width$}", width = decimals as usize)
 }
 
-pub fn mines_left_sprites<'a>(
-    game: &Minesweeper,
-    textures: &'a GameTextures,
-    mines_left_rect: [f64; 4],
-) -> impl Iterator<Item = (Image, &'a Texture<gfx_device_gl::Resources>)> {
-    num_display_sprites(textures, mines_left_rect, game.mines_left)
+// the number of mines left, with at least three digits (or a minus sign and two digits when it is negative,
+// which happens when too many tiles have been flagged)
+pub fn mines_left_text(game: &Minesweeper) -> String {
+    if game.mines_left < 0 {
+        format!("-{:02}", -game.mines_left)
+    } else {
+        format!("{:03}", game.mines_left)
+    }
 }
@@ -0,0 +1,138 @@
+// statistics of the games played on each difficulty, which are kept between runs of the game
+
+use crate::files::{self, FileError};
+use crate::game::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// the version of the statistics file format, which should be increased whenever it changes
+pub const VERSION: u32 = 1;
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct DifficultyStats {
+    pub played: u32,
+    pub won: u32,
+    // the number of games won in a row, up to the last game played
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub best_time_ms: Option<u64>,
+    // the times of all the games won added together, for working out the average
+    pub total_time_ms: u64,
+    // the efficiency measures of the games won (see metrics.rs)
+    pub best_bbbv_per_second: f64,
+    pub best_ioe: f64,
+    // the 3BV of all the games won added together, for working out the average 3BV/s
+    pub total_bbbv: u64,
+    // how much of the board was cleared in every game played added together, for working out the average
+    pub total_completion: f64,
+}
+impl DifficultyStats {
+    // the fraction of games played which were won
+    pub fn win_rate(&self) -> f64 {
+        match self.played {
+            0 => 0.,
+            played => self.won as f64 / played as f64,
+        }
+    }
+
+    pub fn average_time_ms(&self) -> Option<u64> {
+        match self.won {
+            0 => None,
+            won => Some(self.total_time_ms / won as u64),
+        }
+    }
+
+    pub fn average_bbbv_per_second(&self) -> f64 {
+        self.total_bbbv as f64 * 1000. / self.total_time_ms.max(1) as f64
+    }
+
+    // the average fraction of the board cleared in each game
+    pub fn average_completion(&self) -> f64 {
+        match self.played {
+            0 => 0.,
+            played => self.total_completion / played as f64,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Statistics {
+    version: u32,
+    // each custom board size and number of mines has its own statistics
+    difficulties: Vec<(Difficulty, DifficultyStats)>,
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Statistics {
+            version: VERSION,
+            difficulties: Vec::new(),
+        }
+    }
+}
+
+impl Statistics {
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        files::write_json(path, self)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        files::read_json(path, VERSION)
+    }
+
+    pub fn get(&self, difficulty: Difficulty) -> DifficultyStats {
+        self.difficulties
+            .iter()
+            .find(|(d, _)| *d == difficulty)
+            .map(|(_, stats)| stats.clone())
+            .unwrap_or_default()
+    }
+
+    fn get_mut(&mut self, difficulty: Difficulty) -> &mut DifficultyStats {
+        let i = match self.difficulties.iter().position(|(d, _)| *d == difficulty) {
+            Some(i) => i,
+            None => {
+                self.difficulties
+                    .push((difficulty, DifficultyStats::default()));
+                self.difficulties.len() - 1
+            }
+        };
+        &mut self.difficulties[i].1
+    }
+
+    // every difficulty which has had games recorded
+    pub fn difficulties(&self) -> impl Iterator<Item = Difficulty> + '_ {
+        self.difficulties.iter().map(|(difficulty, _)| *difficulty)
+    }
+
+    // adds a finished game to the statistics, returning whether it was added
+    // games which used hints or undo aren't counted, as they aren't fair comparisons
+    pub fn record(&mut self, game: &Minesweeper) -> bool {
+        if game.state == GameState::Playing || !game.counts_towards_records() {
+            return false;
+        }
+        let metrics = game.metrics();
+        let stats = self.get_mut(game.difficulty);
+        stats.played += 1;
+        stats.total_completion += metrics.completion();
+        if game.state == GameState::Won {
+            let time_ms = metrics.time_ms;
+            stats.won += 1;
+            stats.current_streak += 1;
+            stats.longest_streak = u32::max(stats.longest_streak, stats.current_streak);
+            stats.best_time_ms = Some(stats.best_time_ms.map_or(time_ms, |best| best.min(time_ms)));
+            stats.total_time_ms += time_ms;
+            stats.best_bbbv_per_second =
+                f64::max(stats.best_bbbv_per_second, metrics.bbbv_per_second());
+            stats.best_ioe = f64::max(stats.best_ioe, metrics.ioe());
+            stats.total_bbbv += metrics.bbbv as u64;
+        } else {
+            stats.current_streak = 0;
+        }
+        true
+    }
+
+    pub fn reset(&mut self, difficulty: Difficulty) {
+        self.difficulties.retain(|(d, _)| *d != difficulty);
+    }
+}
@@ -0,0 +1,130 @@
+// the dialog shown over the board with the statistics of the games played on each difficulty
+
+use minesweeper::game::*;
+use minesweeper::stats::Statistics;
+use piston_window::*;
+
+use crate::consts::*;
+use crate::dialog::*;
+
+pub struct StatsDialog {
+    // the difficulties that can be looked through, which are the usual ones and any custom boards played
+    difficulties: Vec<Difficulty>,
+    selected: usize,
+    // whether reset has been pressed once, so pressing it again clears the statistics
+    confirm_reset: bool,
+}
+
+impl StatsDialog {
+    // starts on the difficulty of the current game
+    pub fn new(stats: &Statistics, difficulty: Difficulty) -> Self {
+        let mut difficulties = vec![Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+        for d in stats.difficulties().chain([difficulty]) {
+            if !difficulties.contains(&d) {
+                difficulties.push(d);
+            }
+        }
+        StatsDialog {
+            selected: difficulties.iter().position(|d| *d == difficulty).unwrap(),
+            difficulties,
+            confirm_reset: false,
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % self.difficulties.len();
+        self.confirm_reset = false;
+    }
+
+    // the first press asks for confirmation, and the second clears the statistics of the shown difficulty
+    pub fn reset(&mut self, stats: &mut Statistics) -> bool {
+        if self.confirm_reset {
+            stats.reset(self.difficulties[self.selected]);
+            self.confirm_reset = false;
+            true
+        } else {
+            self.confirm_reset = true;
+            false
+        }
+    }
+
+    pub fn draw(
+        &self,
+        stats: &Statistics,
+        dialog_rect: [f64; 4],
+        c: Context,
+        g: &mut G2d,
+        glyphs: &mut Glyphs,
+    ) {
+        let transform = draw_dialog_frame(dialog_rect, c, g);
+
+        let difficulty = self.difficulties[self.selected];
+        let difficulty_stats = stats.get(difficulty);
+        let seconds =
+            |ms: Option<u64>| ms.map_or("-".to_string(), |ms| format!("{:.1}s", ms as f64 / 1000.));
+        let lines = [
+            (difficulty.to_string(), TEXT_SIZE),
+            (
+                format!("Played: {}", difficulty_stats.played),
+                TEXT_SIZE - 4,
+            ),
+            (
+                format!(
+                    "Won: {} ({:.0}%)",
+                    difficulty_stats.won,
+                    difficulty_stats.win_rate() * 100.
+                ),
+                TEXT_SIZE - 4,
+            ),
+            (
+                format!(
+                    "Streak: {} (longest {})",
+                    difficulty_stats.current_streak, difficulty_stats.longest_streak
+                ),
+                TEXT_SIZE - 4,
+            ),
+            (
+                format!("Best time: {}", seconds(difficulty_stats.best_time_ms)),
+                TEXT_SIZE - 4,
+            ),
+            (
+                format!(
+                    "Average time: {}",
+                    seconds(difficulty_stats.average_time_ms())
+                ),
+                TEXT_SIZE - 4,
+            ),
+            (
+                format!(
+                    "3BV/s: best {:.2}, average {:.2}",
+                    difficulty_stats.best_bbbv_per_second,
+                    difficulty_stats.average_bbbv_per_second()
+                ),
+                TEXT_SIZE - 4,
+            ),
+            (
+                format!("Best IOE: {:.2}", difficulty_stats.best_ioe),
+                TEXT_SIZE - 4,
+            ),
+            (
+                format!(
+                    "Average completion: {:.0}%",
+                    difficulty_stats.average_completion() * 100.
+                ),
+                TEXT_SIZE - 4,
+            ),
+        ];
+        let lines = lines.into_iter().enumerate().map(|(i, (line, size))| {
+            let y = if i == 0 { 28. } else { 36. + i as f64 * 16. };
+            (line, size, y)
+        });
+        draw_lines(lines, transform, c, g, glyphs);
+
+        let help = if self.confirm_reset {
+            "Press Delete again to reset"
+        } else {
+            "Tab: next, Delete: reset, S: close"
+        };
+        draw_lines([(help, TEXT_SIZE - 4, 188.)], transform, c, g, glyphs);
+    }
+}
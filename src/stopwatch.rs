@@ -0,0 +1,48 @@
+// a stopwatch which can be stopped and started again, and restored to a previous time when a saved game is loaded
+
+use std::time::{Duration, Instant};
+
+#[derive(Default, Clone, Debug)]
+pub struct Stopwatch {
+    // the time counted before the stopwatch was last started
+    elapsed: Duration,
+    // when the stopwatch was last started, if it is running
+    started: Option<Instant>,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // a stopped stopwatch which has already counted the given time
+    pub fn with_elapsed(elapsed: Duration) -> Self {
+        Stopwatch {
+            elapsed,
+            started: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        // starting a running stopwatch would lose the time since it was started
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.elapsed = self.elapsed();
+        self.started = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.started {
+            Some(started) => self.elapsed + started.elapsed(),
+            None => self.elapsed,
+        }
+    }
+}
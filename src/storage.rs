@@ -0,0 +1,158 @@
+// where the game keeps its files on the player's computer
+
+use minesweeper::files::FileError;
+use minesweeper::game::*;
+use minesweeper::replay::Replay;
+use minesweeper::scores::HighScores;
+use minesweeper::settings::Settings;
+use minesweeper::stats::Statistics;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn data_folder() -> PathBuf {
+    // fall back to the working directory on platforms without a data folder
+    dirs::data_dir().unwrap_or_default().join("minesweeper")
+}
+
+// the current time in seconds since the unix epoch
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+// replays are named by when the game finished, so they sort in the order they were played
+pub fn save_replay(replay: &Replay) -> Result<(), String> {
+    let time = unix_time();
+    let path = data_folder()
+        .join("replays")
+        .join(format!("{time}-{}.json", replay.seed));
+    replay
+        .save(&path)
+        .map_err(|e| format!("couldn't save the replay: {e}"))?;
+    // a copy for other minesweeper programs, unless moves were undone which they can't show
+    match replay.export_rawvf(&path.with_extension("rawvf")) {
+        Ok(()) | Err(FileError::Unsupported(_)) => Ok(()),
+        Err(e) => Err(format!("couldn't save the replay as RAW-VF: {e}")),
+    }
+}
+
+pub enum OpenedFile {
+    Board(Minesweeper),
+    Replay(Replay),
+}
+
+// opens a board to play or a replay to watch, which can be a replay saved by the game (.json),
+// a RAW-VF replay from another program, or a board as text
+// returns what was wrong with the file if it couldn't be opened, so it can be shown to the player
+pub fn open_file(path: &str) -> Result<OpenedFile, String> {
+    let path = Path::new(path);
+    let opened = if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        Replay::load(path).map(OpenedFile::Replay)
+    } else {
+        match std::fs::read_to_string(path) {
+            // RAW-VF files have a board section after their headers
+            Ok(text) if text.lines().any(|line| line.trim() == "Board:") => {
+                Replay::from_rawvf(&text).map(OpenedFile::Replay)
+            }
+            Ok(text) => Minesweeper::from_board_text(&text).map(OpenedFile::Board),
+            Err(e) => Err(e.into()),
+        }
+    };
+    opened.map_err(|e| format!("couldn't open {}: {e}", path.display()))
+}
+
+// exports the board to the boards folder, named the same way as replays
+pub fn export_board(game: &Minesweeper) -> Result<(), String> {
+    let path = data_folder()
+        .join("boards")
+        .join(format!("{}-{}.txt", unix_time(), game.seed));
+    game.export_board(&path)
+        .map_err(|e| format!("couldn't export the board: {e}"))
+}
+
+// loads one of the files kept between runs, which is the default if it hasn't been saved yet
+// returns what was wrong with the file if it couldn't be loaded, so it can be shown to the player
+fn load_or_default<T: Default>(
+    what: &str,
+    path: &Path,
+    load: impl FnOnce(&Path) -> Result<T, FileError>,
+) -> Result<T, String> {
+    match load(path) {
+        Ok(loaded) => Ok(loaded),
+        Err(FileError::Io(e)) if e.kind() == ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(format!(
+            "couldn't load the {what} from {}: {e}",
+            path.display()
+        )),
+    }
+}
+
+fn saved_game_path() -> PathBuf {
+    data_folder().join("saved_game.json")
+}
+
+// the unfinished game from when the window was last closed, if there was one
+pub fn load_saved_game() -> Result<Option<Minesweeper>, String> {
+    load_or_default("saved game", &saved_game_path(), |path| {
+        Minesweeper::load(path).map(Some)
+    })
+}
+
+// saves the game if it is unfinished, or removes the saved game if not so it isn't offered again
+pub fn save_game(game: &Minesweeper) -> Result<(), String> {
+    if game.state == GameState::Playing && game.started() {
+        game.save(&saved_game_path())
+            .map_err(|e| format!("couldn't save the game: {e}"))
+    } else {
+        let _ = std::fs::remove_file(saved_game_path());
+        Ok(())
+    }
+}
+
+fn statistics_path() -> PathBuf {
+    data_folder().join("statistics.json")
+}
+
+// the statistics of every game played, which start empty the first time the game is run
+pub fn load_statistics() -> Result<Statistics, String> {
+    load_or_default("statistics", &statistics_path(), Statistics::load)
+}
+
+pub fn save_statistics(stats: &Statistics) -> Result<(), String> {
+    stats
+        .save(&statistics_path())
+        .map_err(|e| format!("couldn't save the statistics: {e}"))
+}
+
+fn high_scores_path() -> PathBuf {
+    data_folder().join("high_scores.json")
+}
+
+pub fn load_high_scores() -> Result<HighScores, String> {
+    load_or_default("high scores", &high_scores_path(), HighScores::load)
+}
+
+pub fn save_high_scores(scores: &HighScores) -> Result<(), String> {
+    scores
+        .save(&high_scores_path())
+        .map_err(|e| format!("couldn't save the high scores: {e}"))
+}
+
+fn settings_path() -> PathBuf {
+    data_folder().join("settings.json")
+}
+
+pub fn load_settings() -> Result<Settings, String> {
+    load_or_default("settings", &settings_path(), Settings::load)
+}
+
+pub fn save_settings(settings: &Settings) -> Result<(), String> {
+    settings
+        .save(&settings_path())
+        .map_err(|e| format!("couldn't save the settings: {e}"))
+}
@@ -1,34 +1,48 @@
 // structs to contain all the textures used in the game
 
 use piston_window::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct GameTextures {
     pub tile: TileTextures,
     pub border: BorderTextures,
     pub face_buttons: FaceButtonTextures,
+    pub hint_button: HintButtonTextures,
     pub difficulty_buttons: DifficultyButtonTextures,
     pub number_display: NumberDisplayTextures,
 }
 
+fn assets_folder() -> PathBuf {
+    // get the folder of the executable
+    let mut exe_folder = std::env::current_exe().unwrap();
+    // remove the executable's name, leaving the path to the containing folder
+    exe_folder.pop();
+    // find the resources folder starting from the executable's folder
+    let mut assets = find_folder::Search::ParentsThenKids(3, 3)
+        .of(exe_folder)
+        .for_folder("Resources")
+        .unwrap();
+    assets.push("assets");
+    assets
+}
+
+// the font is kept separate from the textures as drawing text needs a mutable reference to it
+pub fn load_glyphs(window: &mut PistonWindow) -> Glyphs {
+    window
+        .load_font(assets_folder().join("fonts/DejaVuSansMono-Bold.ttf"))
+        .unwrap()
+}
+
 impl GameTextures {
     pub fn new(window: &mut PistonWindow) -> Self {
-        // get the folder of the executable
-        let mut exe_folder = std::env::current_exe().unwrap();
-        // remove the executable's name, leaving the path to the containing folder
-        exe_folder.pop();
-        // find the resources folder starting from the executable's folder
-        let mut assets = find_folder::Search::ParentsThenKids(3, 3)
-            .of(exe_folder)
-            .for_folder("Resources")
-            .unwrap();
-        assets.push("assets");
+        let assets = assets_folder();
 
         // return the struct containing all the textures
         GameTextures {
             tile: TileTextures::new(window, &assets),
             border: BorderTextures::new(window, &assets),
             face_buttons: FaceButtonTextures::new(window, &assets),
+            hint_button: HintButtonTextures::new(window, &assets),
             difficulty_buttons: DifficultyButtonTextures::new(window, &assets),
             number_display: NumberDisplayTextures::new(window, &assets),
         }
@@ -36,9 +50,9 @@ impl GameTextures {
 }
 
 trait MinesweeperTexture {
-    fn new(window: &mut PistonWindow, assets: &PathBuf) -> Self;
+    fn new(window: &mut PistonWindow, assets: &Path) -> Self;
 
-    fn load_texture(window: &mut PistonWindow, assets: &PathBuf, path: &str) -> G2dTexture {
+    fn load_texture(window: &mut PistonWindow, assets: &Path, path: &str) -> G2dTexture {
         Texture::from_path(
             &mut window.create_texture_context(),
             assets.join(path),
@@ -52,6 +66,9 @@ trait MinesweeperTexture {
 pub struct TileTextures {
     pub closed: G2dTexture,
     pub flagged: G2dTexture,
+    // a closed tile while a mouse button is held over it
+    pub pressed: G2dTexture,
+    pub questioned: G2dTexture,
 
     pub mine: G2dTexture,
     pub mine_hit: G2dTexture,
@@ -61,10 +78,12 @@ pub struct TileTextures {
 }
 
 impl MinesweeperTexture for TileTextures {
-    fn new(window: &mut PistonWindow, assets: &PathBuf) -> Self {
+    fn new(window: &mut PistonWindow, assets: &Path) -> Self {
         TileTextures {
             closed: Self::load_texture(window, assets, "tiles/tile_closed.png"),
             flagged: Self::load_texture(window, assets, "tiles/tile_flagged.png"),
+            pressed: Self::load_texture(window, assets, "tiles/tile_pressed.png"),
+            questioned: Self::load_texture(window, assets, "tiles/tile_questioned.png"),
 
             mine: Self::load_texture(window, assets, "tiles/tile_mine.png"),
             mine_hit: Self::load_texture(window, assets, "tiles/tile_mine_hit.png"),
@@ -102,7 +121,7 @@ pub struct BorderTextures {
 }
 
 impl MinesweeperTexture for BorderTextures {
-    fn new(window: &mut PistonWindow, assets: &PathBuf) -> Self {
+    fn new(window: &mut PistonWindow, assets: &Path) -> Self {
         BorderTextures {
             top_left: Self::load_texture(window, assets, "border/top_left.png"),
             top_right: Self::load_texture(window, assets, "border/top_right.png"),
@@ -125,14 +144,31 @@ pub struct FaceButtonTextures {
     pub normal: G2dTexture,
     pub won: G2dTexture,
     pub lost: G2dTexture,
+    // while a mouse button is held on the board
+    pub surprised: G2dTexture,
 }
 
 impl MinesweeperTexture for FaceButtonTextures {
-    fn new(window: &mut PistonWindow, assets: &PathBuf) -> Self {
+    fn new(window: &mut PistonWindow, assets: &Path) -> Self {
         FaceButtonTextures {
             normal: Self::load_texture(window, assets, "buttons/face_normal.jpg"),
             won: Self::load_texture(window, assets, "buttons/face_won.jpg"),
             lost: Self::load_texture(window, assets, "buttons/face_lost.jpg"),
+            surprised: Self::load_texture(window, assets, "buttons/face_surprised.jpg"),
+        }
+    }
+}
+
+pub struct HintButtonTextures {
+    pub normal: G2dTexture,
+    pub pressed: G2dTexture,
+}
+
+impl MinesweeperTexture for HintButtonTextures {
+    fn new(window: &mut PistonWindow, assets: &Path) -> Self {
+        HintButtonTextures {
+            normal: Self::load_texture(window, assets, "buttons/hint.jpg"),
+            pressed: Self::load_texture(window, assets, "buttons/hint_pressed.jpg"),
         }
     }
 }
@@ -146,10 +182,13 @@ pub struct DifficultyButtonTextures {
 
     pub hard: G2dTexture,
     pub hard_pressed: G2dTexture,
+
+    pub custom: G2dTexture,
+    pub custom_pressed: G2dTexture,
 }
 
 impl MinesweeperTexture for DifficultyButtonTextures {
-    fn new(window: &mut PistonWindow, assets: &PathBuf) -> Self {
+    fn new(window: &mut PistonWindow, assets: &Path) -> Self {
         DifficultyButtonTextures {
             easy: Self::load_texture(window, assets, "buttons/easy.jpg"),
             easy_pressed: Self::load_texture(window, assets, "buttons/easy_pressed.jpg"),
@@ -159,6 +198,9 @@ impl MinesweeperTexture for DifficultyButtonTextures {
 
             hard: Self::load_texture(window, assets, "buttons/hard.jpg"),
             hard_pressed: Self::load_texture(window, assets, "buttons/hard_pressed.jpg"),
+
+            custom: Self::load_texture(window, assets, "buttons/custom.jpg"),
+            custom_pressed: Self::load_texture(window, assets, "buttons/custom_pressed.jpg"),
         }
     }
 }
@@ -166,10 +208,14 @@ impl MinesweeperTexture for DifficultyButtonTextures {
 pub struct NumberDisplayTextures {
     pub background: G2dTexture,
     pub numbers: [G2dTexture; 10],
+    // the decimal point, for times shown with fractions of a second
+    pub point: G2dTexture,
+    // the minus sign, for when more tiles have been flagged than there are mines
+    pub minus: G2dTexture,
 }
 
 impl MinesweeperTexture for NumberDisplayTextures {
-    fn new(window: &mut PistonWindow, assets: &PathBuf) -> Self {
+    fn new(window: &mut PistonWindow, assets: &Path) -> Self {
         NumberDisplayTextures {
             background: Self::load_texture(window, assets, "number_display/background.jpg"),
             numbers: [
@@ -184,6 +230,8 @@ impl MinesweeperTexture for NumberDisplayTextures {
                 Self::load_texture(window, assets, "number_display/8.jpg"),
                 Self::load_texture(window, assets, "number_display/9.jpg"),
             ],
+            point: Self::load_texture(window, assets, "number_display/point.jpg"),
+            minus: Self::load_texture(window, assets, "number_display/minus.jpg"),
         }
     }
 }
@@ -0,0 +1,117 @@
+// the events of the window, which waits for input instead of drawing frames continually so the game uses no cpu while
+// nothing is happening, and draws the window again only when something in it has changed
+// (things which change over time, like the stopwatch, ask to be woken up when they will next need drawing)
+
+use piston_window::*;
+use std::time::{Duration, Instant};
+
+pub struct WindowEvents {
+    // whether the window needs drawing again, which happens once the events waiting to be handled have been
+    redraw: bool,
+    // a render event has been given out, so the frame is shown before the next event
+    rendered: bool,
+    // when to give out an update event if nothing else has happened by then
+    wake: Option<Instant>,
+    // when the last update event was given out, so updates know how much time has passed
+    last_update: Option<Instant>,
+}
+
+impl WindowEvents {
+    pub fn new() -> Self {
+        WindowEvents {
+            redraw: true,
+            rendered: false,
+            wake: None,
+            last_update: None,
+        }
+    }
+
+    pub fn redraw(&mut self) {
+        self.redraw = true;
+    }
+
+    // asks for an update event (and a redraw) after a delay, or for no more updates if it is None
+    // (this should be called after every event, and an earlier wake up is kept so that a stream of input,
+    // like the mouse moving, can't keep putting it off)
+    pub fn wake_after(&mut self, delay: Option<Duration>) {
+        let now = Instant::now();
+        self.wake = delay.map(|delay| {
+            let wake = now + delay;
+            self.wake.map_or(wake, |earlier| earlier.min(wake))
+        });
+        // the time between updates only counts while they are wanted
+        self.last_update = delay.map(|_| self.last_update.unwrap_or(now));
+    }
+
+    pub fn next(&mut self, window: &mut PistonWindow) -> Option<Event> {
+        let e = self.next_event(window)?;
+        // lets the window resize its frame buffers and clean up after drawing
+        window.event(&e);
+        Some(e)
+    }
+
+    fn next_event(&mut self, window: &mut PistonWindow) -> Option<Event> {
+        loop {
+            if window.should_close() {
+                return None;
+            }
+            if self.rendered {
+                self.rendered = false;
+                window.swap_buffers();
+                return Some(AfterRenderArgs.into());
+            }
+            let now = Instant::now();
+            if self.wake.is_some_and(|wake| wake <= now) {
+                let dt = self
+                    .last_update
+                    .map_or(0., |last| (now - last).as_secs_f64());
+                self.wake = None;
+                self.last_update = Some(now);
+                self.redraw = true;
+                return Some(UpdateArgs { dt }.into());
+            }
+            if let Some(e) = window.poll_event() {
+                return Some(self.input(e));
+            }
+            if self.redraw {
+                self.redraw = false;
+                let size = window.size();
+                // a minimised window can't be drawn
+                if size.width != 0. && size.height != 0. {
+                    self.rendered = true;
+                    return Some(
+                        RenderArgs {
+                            ext_dt: 0.,
+                            window_size: size.into(),
+                            draw_size: window.draw_size().into(),
+                        }
+                        .into(),
+                    );
+                }
+            }
+            // sleep until there is some input, or until it is time to wake up
+            let e = match self.wake {
+                Some(wake) => window.wait_event_timeout(wake.saturating_duration_since(now)),
+                None => Some(window.wait_event()),
+            };
+            if let Some(e) = e {
+                return Some(self.input(e));
+            }
+        }
+    }
+
+    // any input could change what is shown, apart from moving the mouse, which only needs a redraw if it moves
+    // onto a different tile (so the caller asks for that itself)
+    fn input(&mut self, e: Event) -> Event {
+        if !matches!(
+            e,
+            Event::Input(
+                Input::Move(Motion::MouseCursor(_) | Motion::MouseRelative(_)),
+                _
+            )
+        ) {
+            self.redraw = true;
+        }
+        e
+    }
+}